@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use std::{env, fs};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Parser {
+    // Named captures become fields, `message` and `type` captures are used for the log itself
+    Regex {
+        pattern: String,
+    },
+    // Objects become fields, the message and type are read from the given fields
+    Json {
+        message_field: Option<String>,
+        type_field: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SourceKind {
+    // Tails a file, following rotations
+    File {
+        path: String,
+        // Read the file from its start the first time instead of only new lines
+        #[serde(default)]
+        from_beginning: bool,
+    },
+    // Reads the output of a long running command, e.g. `journalctl --follow`
+    Command {
+        command: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Source {
+    #[serde(flatten)]
+    pub kind: SourceKind,
+    // Type of the logs when the parser doesn't find one
+    pub r#type: Option<String>,
+    pub parser: Option<Parser>,
+}
+
+fn default_batch_size() -> usize {
+    500
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_max_spool_files() -> usize {
+    1000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub server_url: String,
+    pub token: String,
+    pub app_id: String,
+    pub state_dir: String,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    // Batches kept on disk while the server is unreachable, the oldest are deleted past it
+    #[serde(default = "default_max_spool_files")]
+    pub max_spool_files: usize,
+    pub sources: Vec<Source>,
+}
+
+pub fn load() -> Config {
+    let args: Vec<String> = env::args().collect();
+    let config_path = if args.len() <= 1 {
+        "agent.toml"
+    } else {
+        args.get(1).unwrap()
+    };
+    let file_contents = fs::read_to_string(config_path);
+    if file_contents.is_err() {
+        panic!("error: unable to read file with path \"{}\"", config_path);
+    }
+
+    match toml::from_str(file_contents.unwrap().as_str()) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            panic!("error: unable to deserialize config. {}", err);
+        }
+    }
+}
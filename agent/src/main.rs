@@ -0,0 +1,59 @@
+mod config;
+mod parser;
+mod shipper;
+mod sources;
+mod state;
+
+use config::SourceKind;
+use parser::LineParser;
+use shipper::Shipper;
+use tokio::sync::mpsc;
+
+#[tokio::main]
+async fn main() {
+    let config = config::load();
+
+    std::fs::create_dir_all(&config.state_dir).expect("Failed to create the state directory");
+    let offsets = state::load_offsets(&config.state_dir);
+
+    let (sender, receiver) = mpsc::channel(config.batch_size * 4);
+    for source in config.sources.clone() {
+        let parser = match LineParser::new(&source.parser) {
+            Ok(parser) => parser,
+            Err(err) => panic!("error: invalid parser. {}", err),
+        };
+        match source.kind.clone() {
+            SourceKind::File {
+                path,
+                from_beginning,
+            } => {
+                println!("📄 Tailing {}", path);
+                let saved = offsets.get(&path).copied();
+                tokio::spawn(sources::tail_file(
+                    source,
+                    parser,
+                    path,
+                    from_beginning,
+                    saved,
+                    sender.clone(),
+                ));
+            }
+            SourceKind::Command { command } => {
+                if command.is_empty() {
+                    panic!("error: a command source needs a command");
+                }
+                println!("📄 Reading the output of {}", command.join(" "));
+                tokio::spawn(sources::read_command(
+                    source,
+                    parser,
+                    command,
+                    sender.clone(),
+                ));
+            }
+        }
+    }
+    drop(sender);
+
+    println!("🚀 Agent started, shipping to {}", config.server_url);
+    Shipper::new(config, offsets).run(receiver).await;
+}
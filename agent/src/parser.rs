@@ -0,0 +1,84 @@
+use regex::Regex;
+use serde_json::{Map, Value};
+use watchtower_types::LogInput;
+
+use crate::config::{Parser, Source};
+
+// A parser compiled once per source
+pub enum LineParser {
+    Raw,
+    Regex(Regex),
+    Json {
+        message_field: String,
+        type_field: String,
+    },
+}
+
+impl LineParser {
+    pub fn new(parser: &Option<Parser>) -> Result<Self, String> {
+        match parser {
+            None => Ok(LineParser::Raw),
+            Some(Parser::Regex { pattern }) => Regex::new(pattern)
+                .map(LineParser::Regex)
+                .map_err(|err| format!("Invalid pattern: {}", err)),
+            Some(Parser::Json {
+                message_field,
+                type_field,
+            }) => Ok(LineParser::Json {
+                message_field: message_field.clone().unwrap_or("message".to_string()),
+                type_field: type_field.clone().unwrap_or("level".to_string()),
+            }),
+        }
+    }
+
+    // Lines that don't match the parser are sent as is
+    pub fn parse(&self, source: &Source, line: &str) -> LogInput {
+        let mut log = LogInput {
+            r#type: source.r#type.clone(),
+            message: line.to_string(),
+            ..Default::default()
+        };
+
+        match self {
+            LineParser::Raw => {}
+            LineParser::Regex(regex) => {
+                let Some(captures) = regex.captures(line) else {
+                    return log;
+                };
+                let mut fields = Map::new();
+                for name in regex.capture_names().flatten() {
+                    let Some(value) = captures.name(name) else {
+                        continue;
+                    };
+                    match name {
+                        "message" => log.message = value.as_str().to_string(),
+                        "type" => log.r#type = Some(value.as_str().to_lowercase()),
+                        name => {
+                            fields.insert(name.to_string(), Value::from(value.as_str()));
+                        }
+                    }
+                }
+                log.fields = Some(Value::Object(fields));
+            }
+            LineParser::Json {
+                message_field,
+                type_field,
+            } => {
+                let Ok(Value::Object(mut fields)) = serde_json::from_str(line) else {
+                    return log;
+                };
+                if let Some(message) = fields.remove(message_field) {
+                    log.message = match message {
+                        Value::String(message) => message,
+                        message => message.to_string(),
+                    };
+                }
+                if let Some(Value::String(r#type)) = fields.remove(type_field) {
+                    log.r#type = Some(r#type.to_lowercase());
+                }
+                log.fields = Some(Value::Object(fields));
+            }
+        }
+        log
+    }
+}
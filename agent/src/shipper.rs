@@ -0,0 +1,314 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use reqwest::{header, StatusCode};
+use tokio::sync::mpsc;
+use watchtower_types::{AddMessagesInput, LogInput};
+
+use crate::{
+    config::Config,
+    sources::Line,
+    state::{save_offsets, Offsets},
+};
+
+pub struct Shipper {
+    config: Config,
+    http: reqwest::Client,
+    offsets: Offsets,
+    spool_dir: PathBuf,
+    spooled: u64,
+}
+
+enum SendError {
+    // The server can't be reached or is overloaded, the batch is kept for later
+    Unavailable(String),
+    // The server refused the batch (invalid token...), sending it again won't help
+    Rejected(String),
+}
+
+impl Shipper {
+    pub fn new(config: Config, offsets: Offsets) -> Self {
+        let spool_dir = Path::new(&config.state_dir).join("spool");
+        fs::create_dir_all(&spool_dir).expect("Failed to create the spool directory");
+        Shipper {
+            config,
+            http: reqwest::Client::new(),
+            offsets,
+            spool_dir,
+            spooled: 0,
+        }
+    }
+
+    pub async fn run(mut self, mut receiver: mpsc::Receiver<Line>) {
+        let mut batch = Vec::new();
+        let mut pending_offsets = Offsets::new();
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.flush_interval_ms));
+
+        loop {
+            tokio::select! {
+                line = receiver.recv() => {
+                    let Some(line) = line else {
+                        self.flush(std::mem::take(&mut batch), std::mem::take(&mut pending_offsets)).await;
+                        return;
+                    };
+                    batch.push(line.log);
+                    if let Some((path, offset)) = line.offset {
+                        pending_offsets.insert(path, offset);
+                    }
+                    if batch.len() >= self.config.batch_size {
+                        self.flush(std::mem::take(&mut batch), std::mem::take(&mut pending_offsets)).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    self.flush(std::mem::take(&mut batch), std::mem::take(&mut pending_offsets)).await;
+                }
+            }
+        }
+    }
+
+    // Offsets are saved once the logs are either shipped or written to the spool
+    async fn flush(&mut self, batch: Vec<LogInput>, pending_offsets: Offsets) {
+        let spool_sent = self.send_spool().await;
+
+        if !batch.is_empty() {
+            // Keep the order of the logs, new batches wait behind the spooled ones
+            if !spool_sent {
+                self.spool(&batch);
+            } else if let Err(err) = self.send(&batch).await {
+                match err {
+                    SendError::Unavailable(err) => {
+                        println!(
+                            "❌ Server unavailable, spooling {} logs: {}",
+                            batch.len(),
+                            err
+                        );
+                        self.spool(&batch);
+                    }
+                    SendError::Rejected(err) => {
+                        println!("❌ {} logs rejected by the server: {}", batch.len(), err);
+                    }
+                }
+            }
+        }
+
+        if !pending_offsets.is_empty() {
+            self.offsets.extend(pending_offsets);
+            save_offsets(&self.config.state_dir, &self.offsets);
+        }
+    }
+
+    async fn send(&self, logs: &[LogInput]) -> Result<(), SendError> {
+        let body = serde_json::to_vec(&AddMessagesInput {
+            token: self.config.token.clone(),
+            app_id: Some(self.config.app_id.clone()),
+            logs: logs.to_vec(),
+        })
+        .unwrap();
+        let res = self
+            .http
+            .post(format!(
+                "{}/service/add_messages",
+                self.config.server_url.trim_end_matches('/')
+            ))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| SendError::Unavailable(err.to_string()))?;
+
+        let status = res.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let message = format!("{}: {}", status, res.text().await.unwrap_or_default());
+        if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+            Err(SendError::Rejected(message))
+        } else {
+            Err(SendError::Unavailable(message))
+        }
+    }
+
+    fn spool(&mut self, logs: &[LogInput]) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        // Names sort in the order the batches were spooled
+        let path = self
+            .spool_dir
+            .join(format!("{:020}-{:010}.json", now, self.spooled));
+        self.spooled += 1;
+        if let Err(err) = fs::write(&path, serde_json::to_vec(logs).unwrap()) {
+            println!("❌ Failed to spool {} logs: {}", logs.len(), err);
+            return;
+        }
+
+        let files = self.get_spool_files();
+        if files.len() > self.config.max_spool_files {
+            for file in &files[..files.len() - self.config.max_spool_files] {
+                println!("❌ Spool full, deleting {}", file.display());
+                let _ = fs::remove_file(file);
+            }
+        }
+    }
+
+    fn get_spool_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.spool_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.extension()
+                            .is_some_and(|extension| extension == "json")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    // Returns whether the spool is empty afterwards
+    async fn send_spool(&mut self) -> bool {
+        for file in self.get_spool_files() {
+            let logs: Vec<LogInput> = match fs::read(&file)
+                .ok()
+                .and_then(|logs| serde_json::from_slice(&logs).ok())
+            {
+                Some(logs) => logs,
+                None => {
+                    println!("❌ Invalid spool file, deleting {}", file.display());
+                    let _ = fs::remove_file(&file);
+                    continue;
+                }
+            };
+            match self.send(&logs).await {
+                Ok(()) => {}
+                Err(SendError::Rejected(err)) => {
+                    println!(
+                        "❌ {} spooled logs rejected by the server: {}",
+                        logs.len(),
+                        err
+                    );
+                }
+                Err(SendError::Unavailable(_)) => return false,
+            }
+            let _ = fs::remove_file(&file);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{load_offsets, FileOffset};
+    use watchtower_test_support::{temp_dir, StubServer};
+
+    fn config(server: &StubServer, state_dir: &str) -> Config {
+        Config {
+            server_url: server.url.clone(),
+            token: "token".to_string(),
+            app_id: "app".to_string(),
+            state_dir: state_dir.to_string(),
+            batch_size: 10,
+            flush_interval_ms: 1000,
+            max_spool_files: 1000,
+            sources: Vec::new(),
+        }
+    }
+
+    fn batch(messages: &[&str]) -> Vec<LogInput> {
+        messages
+            .iter()
+            .map(|message| LogInput {
+                message: message.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    // Messages of each request received by the server
+    fn sent_messages(server: &StubServer) -> Vec<Vec<String>> {
+        server
+            .requests()
+            .into_iter()
+            .map(|(path, body)| {
+                assert_eq!(path, "/service/add_messages");
+                let body: AddMessagesInput = serde_json::from_slice(&body).unwrap();
+                body.logs.into_iter().map(|log| log.message).collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn spools_while_unavailable_and_replays_in_order() {
+        let server = StubServer::start().await;
+        let state_dir = temp_dir("spool-replay");
+        let mut shipper = Shipper::new(config(&server, &state_dir), Offsets::new());
+
+        server.respond(503, "unavailable");
+        let offsets = Offsets::from([(
+            "app.log".to_string(),
+            FileOffset {
+                inode: 1,
+                offset: 10,
+            },
+        )]);
+        shipper.flush(batch(&["a"]), offsets).await;
+        assert_eq!(shipper.get_spool_files().len(), 1);
+        // Spooled logs count as handled, the offset is saved
+        assert_eq!(load_offsets(&state_dir)["app.log"].offset, 10);
+
+        // The spool is retried first, the new batch waits behind it
+        server.respond(503, "unavailable");
+        shipper.flush(batch(&["b"]), Offsets::new()).await;
+        assert_eq!(shipper.get_spool_files().len(), 2);
+
+        shipper.flush(batch(&["c", "d"]), Offsets::new()).await;
+        assert!(shipper.get_spool_files().is_empty());
+        assert_eq!(
+            sent_messages(&server),
+            vec![vec!["a"], vec!["a"], vec!["a"], vec!["b"], vec!["c", "d"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_batches_when_spool_is_full() {
+        let server = StubServer::start().await;
+        let state_dir = temp_dir("spool-full");
+        let mut config = config(&server, &state_dir);
+        config.max_spool_files = 2;
+        let mut shipper = Shipper::new(config, Offsets::new());
+
+        for message in ["a", "b", "c"] {
+            server.respond(503, "unavailable");
+            shipper.flush(batch(&[message]), Offsets::new()).await;
+        }
+        assert_eq!(shipper.get_spool_files().len(), 2);
+
+        shipper.flush(Vec::new(), Offsets::new()).await;
+        let sent = sent_messages(&server);
+        assert_eq!(sent[sent.len() - 2..], [vec!["b"], vec!["c"]]);
+    }
+
+    #[tokio::test]
+    async fn rejected_batches_are_not_spooled() {
+        let server = StubServer::start().await;
+        let state_dir = temp_dir("spool-rejected");
+        let mut shipper = Shipper::new(config(&server, &state_dir), Offsets::new());
+
+        server.respond(401, "Invalid token or token expired");
+        shipper.flush(batch(&["a"]), Offsets::new()).await;
+        assert!(shipper.get_spool_files().is_empty());
+
+        // Rate limiting is transient
+        server.respond(429, "Rate limit exceeded");
+        shipper.flush(batch(&["b"]), Offsets::new()).await;
+        assert_eq!(shipper.get_spool_files().len(), 1);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{io::SeekFrom, os::unix::fs::MetadataExt, process::Stdio, time::Duration};
+
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncSeekExt, BufReader},
+    process::Command,
+    sync::mpsc,
+};
+use watchtower_types::LogInput;
+
+use crate::{config::Source, parser::LineParser, state::FileOffset};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+pub struct Line {
+    pub log: LogInput,
+    // Position of the file after this line, persisted once the line is shipped
+    pub offset: Option<(String, FileOffset)>,
+}
+
+fn to_line(parser: &LineParser, source: &Source, line: &[u8]) -> Option<LogInput> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.trim().is_empty() {
+        return None;
+    }
+    let mut log = parser.parse(source, line);
+    // Logs can stay in the spool for a while, keep the time they were read at
+    log.timestamp = Some(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64,
+    );
+    Some(log)
+}
+
+pub async fn tail_file(
+    source: Source,
+    parser: LineParser,
+    path: String,
+    from_beginning: bool,
+    mut saved: Option<FileOffset>,
+    sender: mpsc::Sender<Line>,
+) {
+    let mut first_open = true;
+    loop {
+        let file = match File::open(&path).await {
+            Ok(file) => file,
+            Err(_) => {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+        let metadata = file.metadata().await.unwrap();
+        let inode = metadata.ino();
+
+        // Resume from the saved offset unless the file was rotated or truncated meanwhile
+        let mut offset = match saved.take() {
+            Some(saved) if saved.inode == inode && saved.offset <= metadata.len() => saved.offset,
+            Some(_) => 0,
+            None if first_open && !from_beginning => metadata.len(),
+            None => 0,
+        };
+        first_open = false;
+
+        let mut reader = BufReader::new(file);
+        if reader.seek(SeekFrom::Start(offset)).await.is_err() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+
+        let mut line = Vec::new();
+        loop {
+            match reader.read_until(b'\n', &mut line).await {
+                // End of the file for now, check whether it was rotated or truncated
+                Ok(0) => match tokio::fs::metadata(&path).await {
+                    Ok(metadata) if metadata.ino() != inode => break,
+                    Ok(metadata) if metadata.len() < offset => {
+                        if reader.seek(SeekFrom::Start(0)).await.is_err() {
+                            break;
+                        }
+                        offset = 0;
+                        line.clear();
+                    }
+                    _ => tokio::time::sleep(POLL_INTERVAL).await,
+                },
+                Ok(read) => {
+                    offset += read as u64;
+                    // Incomplete lines are kept until the writer finishes them
+                    if !line.ends_with(b"\n") {
+                        continue;
+                    }
+                    if let Some(log) = to_line(&parser, &source, &line) {
+                        let offset = Some((path.clone(), FileOffset { inode, offset }));
+                        if sender.send(Line { log, offset }).await.is_err() {
+                            return;
+                        }
+                    }
+                    line.clear();
+                }
+                Err(err) => {
+                    println!("❌ Failed to read {}: {}", path, err);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// The command is restarted when it exits
+pub async fn read_command(
+    source: Source,
+    parser: LineParser,
+    command: Vec<String>,
+    sender: mpsc::Sender<Line>,
+) {
+    loop {
+        let child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                println!("❌ Failed to start {}: {}", command[0], err);
+                tokio::time::sleep(RESTART_DELAY).await;
+                continue;
+            }
+        };
+
+        let mut reader = BufReader::new(child.stdout.take().unwrap());
+        let mut line = Vec::new();
+        while let Ok(read) = reader.read_until(b'\n', &mut line).await {
+            if read == 0 {
+                break;
+            }
+            if let Some(log) = to_line(&parser, &source, &line) {
+                if sender.send(Line { log, offset: None }).await.is_err() {
+                    return;
+                }
+            }
+            line.clear();
+        }
+
+        let _ = child.wait().await;
+        println!("❌ {} exited, restarting it", command[0]);
+        tokio::time::sleep(RESTART_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::config::SourceKind;
+    use watchtower_test_support::temp_dir;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn source(path: &str) -> Source {
+        Source {
+            kind: SourceKind::File {
+                path: path.to_string(),
+                from_beginning: true,
+            },
+            r#type: None,
+            parser: None,
+        }
+    }
+
+    fn append(path: &str, lines: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(lines.as_bytes()).unwrap();
+    }
+
+    fn start(path: &str, saved: Option<FileOffset>) -> mpsc::Receiver<Line> {
+        let (sender, receiver) = mpsc::channel(100);
+        tokio::spawn(tail_file(
+            source(path),
+            LineParser::Raw,
+            path.to_string(),
+            true,
+            saved,
+            sender,
+        ));
+        receiver
+    }
+
+    async fn next(receiver: &mut mpsc::Receiver<Line>) -> Line {
+        tokio::time::timeout(TIMEOUT, receiver.recv())
+            .await
+            .expect("no line received")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn resumes_from_saved_offset() {
+        let path = format!("{}/app.log", temp_dir("tail-resume"));
+        append(&path, "first\nsecond\n");
+        let inode = std::fs::metadata(&path).unwrap().ino();
+
+        let mut receiver = start(&path, Some(FileOffset { inode, offset: 6 }));
+        let line = next(&mut receiver).await;
+        assert_eq!(line.log.message, "second");
+        let (offset_path, offset) = line.offset.unwrap();
+        assert_eq!(offset_path, path);
+        assert_eq!((offset.inode, offset.offset), (inode, 13));
+    }
+
+    #[tokio::test]
+    async fn ignores_offset_of_another_file() {
+        let path = format!("{}/app.log", temp_dir("tail-other-inode"));
+        append(&path, "first\nsecond\n");
+        let inode = std::fs::metadata(&path).unwrap().ino();
+
+        let mut receiver = start(
+            &path,
+            Some(FileOffset {
+                inode: inode + 1,
+                offset: 6,
+            }),
+        );
+        assert_eq!(next(&mut receiver).await.log.message, "first");
+    }
+
+    #[tokio::test]
+    async fn waits_for_incomplete_lines() {
+        let path = format!("{}/app.log", temp_dir("tail-incomplete"));
+        append(&path, "partial");
+
+        let mut receiver = start(&path, None);
+        tokio::time::sleep(POLL_INTERVAL).await;
+        append(&path, " line\n");
+        assert_eq!(next(&mut receiver).await.log.message, "partial line");
+    }
+
+    #[tokio::test]
+    async fn follows_rotated_file() {
+        let dir = temp_dir("tail-rotation");
+        let path = format!("{}/app.log", dir);
+        append(&path, "before\n");
+
+        let mut receiver = start(&path, None);
+        assert_eq!(next(&mut receiver).await.log.message, "before");
+
+        std::fs::rename(&path, format!("{}/app.log.1", dir)).unwrap();
+        append(&path, "after\n");
+        let line = next(&mut receiver).await;
+        assert_eq!(line.log.message, "after");
+        let (_, offset) = line.offset.unwrap();
+        assert_eq!(offset.inode, std::fs::metadata(&path).unwrap().ino());
+        assert_eq!(offset.offset, 6);
+    }
+
+    #[tokio::test]
+    async fn restarts_truncated_file() {
+        let path = format!("{}/app.log", temp_dir("tail-truncation"));
+        append(&path, "a long first line\n");
+
+        let mut receiver = start(&path, None);
+        assert_eq!(next(&mut receiver).await.log.message, "a long first line");
+
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(0)
+            .unwrap();
+        append(&path, "short\n");
+        let line = next(&mut receiver).await;
+        assert_eq!(line.log.message, "short");
+        assert_eq!(line.offset.unwrap().1.offset, 6);
+    }
+}
@@ -0,0 +1,31 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+// Position in a file, the inode tells whether the file was rotated since
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+pub struct FileOffset {
+    pub inode: u64,
+    pub offset: u64,
+}
+
+pub type Offsets = HashMap<String, FileOffset>;
+
+pub fn load_offsets(state_dir: &str) -> Offsets {
+    let path = Path::new(state_dir).join("offsets.json");
+    match fs::read_to_string(path) {
+        Ok(offsets) => serde_json::from_str(&offsets).unwrap_or_default(),
+        Err(_) => Offsets::new(),
+    }
+}
+
+// Written to a temporary file first so a crash never leaves a truncated state
+pub fn save_offsets(state_dir: &str, offsets: &Offsets) {
+    let path = Path::new(state_dir).join("offsets.json");
+    let tmp_path = Path::new(state_dir).join("offsets.json.tmp");
+    let res = fs::write(&tmp_path, serde_json::to_vec(offsets).unwrap())
+        .and_then(|_| fs::rename(&tmp_path, &path));
+    if let Err(err) = res {
+        println!("❌ Failed to save offsets: {}", err);
+    }
+}
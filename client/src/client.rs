@@ -0,0 +1,317 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use reqwest::{header, StatusCode};
+use tokio::sync::{mpsc, oneshot};
+use watchtower_types::{AddMessagesInput, LogInput};
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    // Url of the Watchtower server, e.g. "https://watchtower.example.com"
+    pub base_url: String,
+    pub token: String,
+    pub app_id: String,
+    // Maximum number of logs sent per request
+    pub batch_size: usize,
+    // Pending logs are sent at least this often
+    pub flush_interval: Duration,
+    // Logs waiting to be sent, new logs are dropped once it is reached (e.g. server unreachable)
+    pub max_buffered_logs: usize,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl ClientConfig {
+    pub fn new(base_url: &str, token: &str, app_id: &str) -> Self {
+        ClientConfig {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            app_id: app_id.to_string(),
+            batch_size: 100,
+            flush_interval: Duration::from_secs(2),
+            max_buffered_logs: 10_000,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+enum Command {
+    Log(LogInput),
+    Flush(oneshot::Sender<()>),
+}
+
+// Cheap to clone, every clone feeds the same background task
+#[derive(Clone)]
+pub struct Client {
+    sender: mpsc::Sender<Command>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Client {
+    // Spawns the task sending the logs, must be called from a tokio runtime
+    pub fn new(config: ClientConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.max_buffered_logs.max(1));
+        tokio::spawn(run(config, receiver));
+        Client {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn log(&self, r#type: &str, message: &str) {
+        self.send(LogInput {
+            r#type: Some(r#type.to_string()),
+            message: message.to_string(),
+            timestamp: Some(crate::now()),
+            ..Default::default()
+        });
+    }
+
+    // Never blocks, the log is dropped when the buffer is full
+    pub fn send(&self, log: LogInput) {
+        if self.sender.try_send(Command::Log(log)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Number of logs dropped because the buffer was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    // Waits until every log sent before the call has been delivered or given up on
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(Command::Flush(done)).await.is_ok() {
+            let _ = wait.await;
+        }
+    }
+}
+
+async fn run(config: ClientConfig, mut receiver: mpsc::Receiver<Command>) {
+    let http = reqwest::Client::new();
+    let mut batch = Vec::new();
+    // The first tick of `interval` is immediate and would send a partial batch
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + config.flush_interval,
+        config.flush_interval,
+    );
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Log(log)) => {
+                    batch.push(log);
+                    if batch.len() >= config.batch_size {
+                        send_batch(&http, &config, std::mem::take(&mut batch)).await;
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    if !batch.is_empty() {
+                        send_batch(&http, &config, std::mem::take(&mut batch)).await;
+                    }
+                    let _ = done.send(());
+                }
+                // Every client was dropped
+                None => {
+                    if !batch.is_empty() {
+                        send_batch(&http, &config, batch).await;
+                    }
+                    return;
+                }
+            },
+            _ = interval.tick() => {
+                if !batch.is_empty() {
+                    send_batch(&http, &config, std::mem::take(&mut batch)).await;
+                }
+            }
+        }
+    }
+}
+
+async fn send_batch(http: &reqwest::Client, config: &ClientConfig, logs: Vec<LogInput>) {
+    let logs_len = logs.len();
+    let body = serde_json::to_vec(&AddMessagesInput {
+        token: config.token.clone(),
+        app_id: Some(config.app_id.clone()),
+        logs,
+    })
+    .unwrap();
+
+    let mut backoff = config.initial_backoff;
+    for attempt in 0..=config.max_retries {
+        let res = http
+            .post(format!("{}/service/add_messages", config.base_url))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+            .await;
+
+        let mut wait = backoff;
+        match res {
+            Ok(response) if response.status().is_success() => return,
+            // Retrying won't fix an invalid token or body
+            Ok(response)
+                if response.status().is_client_error()
+                    && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                eprintln!(
+                    "watchtower: {} logs rejected ({}): {}",
+                    logs_len,
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                );
+                return;
+            }
+            Ok(response) => {
+                let retry_after = response
+                    .headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse().ok())
+                    .map(Duration::from_secs);
+                if let Some(retry_after) = retry_after {
+                    wait = wait.max(retry_after);
+                }
+            }
+            Err(_) => {}
+        }
+
+        if attempt < config.max_retries {
+            tokio::time::sleep(wait).await;
+            backoff = (backoff * 2).min(config.max_backoff);
+        }
+    }
+    eprintln!(
+        "watchtower: {} logs dropped after {} retries",
+        logs_len, config.max_retries
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use serde_json::Value;
+
+    use super::*;
+    use watchtower_test_support::StubServer;
+
+    fn client(server: &StubServer, batch_size: usize, max_buffered_logs: usize) -> Client {
+        Client::new(ClientConfig {
+            batch_size,
+            flush_interval: Duration::from_secs(3600),
+            max_buffered_logs,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(10),
+            ..ClientConfig::new(&server.url, "token", "app")
+        })
+    }
+
+    // Messages of every request received by the stub
+    fn batches(server: &StubServer) -> Vec<Vec<String>> {
+        server
+            .requests()
+            .into_iter()
+            .map(|(path, body)| {
+                assert_eq!(path, "/service/add_messages");
+                let body: Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(body["token"], "token");
+                assert_eq!(body["app_id"], "app");
+                body["logs"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|log| log["message"].as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sends_full_batches_then_the_rest_on_flush() {
+        let server = StubServer::start().await;
+        let client = client(&server, 3, 100);
+        for id in 1..=7 {
+            client.log("info", &id.to_string());
+        }
+        client.flush().await;
+
+        assert_eq!(
+            batches(&server),
+            vec![vec!["1", "2", "3"], vec!["4", "5", "6"], vec!["7"]]
+        );
+        assert_eq!(client.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        let server = StubServer::start().await;
+        server.respond(500, "{}");
+        server.respond(503, "{}");
+        let client = client(&server, 10, 100);
+        client.log("info", "hello");
+        client.flush().await;
+
+        assert_eq!(batches(&server), vec![vec!["hello"]; 3]);
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after_when_rate_limited() {
+        let server = StubServer::start().await;
+        server.respond_with_headers(429, &[("Retry-After", "1")], "{}");
+        let client = client(&server, 10, 100);
+        let started = Instant::now();
+        client.log("info", "hello");
+        client.flush().await;
+
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(batches(&server), vec![vec!["hello"]; 2]);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let server = StubServer::start().await;
+        server.respond(400, "{\"status\":\"error\"}");
+        let client = client(&server, 10, 100);
+        client.log("info", "hello");
+        client.flush().await;
+
+        assert_eq!(batches(&server), vec![vec!["hello"]]);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = StubServer::start().await;
+        for _ in 0..10 {
+            server.respond(500, "{}");
+        }
+        let client = client(&server, 10, 100);
+        client.log("info", "hello");
+        client.flush().await;
+
+        assert_eq!(batches(&server).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn counts_logs_dropped_when_the_buffer_is_full() {
+        let server = StubServer::start().await;
+        let client = client(&server, 10, 2);
+        // The background task can't run before the next await, so the buffer fills up
+        for id in 1..=5 {
+            client.log("info", &id.to_string());
+        }
+        assert_eq!(client.dropped(), 3);
+        client.flush().await;
+
+        assert_eq!(batches(&server), vec![vec!["1", "2"]]);
+    }
+}
@@ -0,0 +1,98 @@
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::layer::{Context, Layer};
+use watchtower_types::LogInput;
+
+use crate::client::Client;
+
+// Sends tracing events to Watchtower, e.g. `tracing_subscriber::registry().with(WatchtowerLayer::new(client))`
+pub struct WatchtowerLayer {
+    client: Client,
+}
+
+impl WatchtowerLayer {
+    pub fn new(client: Client) -> Self {
+        WatchtowerLayer { client }
+    }
+}
+
+pub fn level_to_type(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "error",
+        Level::WARN => "warning",
+        Level::INFO => "info",
+        Level::DEBUG | Level::TRACE => "debug",
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn record(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                Value::String(message) => message,
+                value => value.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record(field, Value::from(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, Value::from(value));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for WatchtowerLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if crate::is_ignored_target(metadata.target()) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        visitor
+            .fields
+            .insert("target".to_string(), Value::from(metadata.target()));
+
+        self.client.send(LogInput {
+            _id: None,
+            r#type: Some(level_to_type(metadata.level()).to_string()),
+            message: visitor.message,
+            timestamp: Some(crate::now()),
+            fields: Some(Value::Object(visitor.fields)),
+            ..Default::default()
+        });
+    }
+}
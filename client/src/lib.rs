@@ -0,0 +1,30 @@
+pub mod client;
+pub mod layer;
+pub mod logger;
+
+pub use client::{Client, ClientConfig};
+pub use layer::WatchtowerLayer;
+pub use logger::WatchtowerLogger;
+pub use watchtower_types::LogInput;
+
+// Events of the HTTP stack used by the client are ignored, they would be sent back to Watchtower
+pub(crate) fn is_ignored_target(target: &str) -> bool {
+    [
+        "watchtower_client",
+        "reqwest",
+        "hyper",
+        "h2",
+        "rustls",
+        "want",
+        "mio",
+    ]
+    .iter()
+    .any(|ignored| target.starts_with(ignored))
+}
+
+pub(crate) fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
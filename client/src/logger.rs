@@ -0,0 +1,67 @@
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde_json::{Map, Value};
+use watchtower_types::LogInput;
+
+use crate::client::Client;
+
+// `log` backend sending records to Watchtower
+pub struct WatchtowerLogger {
+    client: Client,
+    level: LevelFilter,
+}
+
+impl WatchtowerLogger {
+    pub fn new(client: Client, level: LevelFilter) -> Self {
+        WatchtowerLogger { client, level }
+    }
+
+    // Installs the logger as the global `log` backend
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+pub fn level_to_type(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warning",
+        Level::Info => "info",
+        Level::Debug | Level::Trace => "debug",
+    }
+}
+
+impl Log for WatchtowerLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && !crate::is_ignored_target(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut fields = Map::new();
+        fields.insert("target".to_string(), Value::from(record.target()));
+        if let (Some(file), Some(line)) = (record.file(), record.line()) {
+            fields.insert(
+                "location".to_string(),
+                Value::from(format!("{}:{}", file, line)),
+            );
+        }
+
+        self.client.send(LogInput {
+            _id: None,
+            r#type: Some(level_to_type(record.level()).to_string()),
+            message: record.args().to_string(),
+            timestamp: Some(crate::now()),
+            fields: Some(Value::Object(fields)),
+            ..Default::default()
+        });
+    }
+
+    // Records are sent by a background task, use `Client::flush` to wait for them
+    fn flush(&self) {}
+}
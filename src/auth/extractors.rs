@@ -0,0 +1,227 @@
+use std::{net::SocketAddr, ops::Deref, sync::Arc};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::{
+    auth::legacy_token::LegacyToken,
+    structs::{Role, RoleGrant},
+    utils::{
+        check_auth_token::check_auth_token,
+        get_token_data::get_token_data,
+        has_role::has_role,
+        logs_service_side::get_bearer_token::get_bearer_token,
+        user::{
+            api_key::{check_api_key::check_api_key, generate_api_key::API_KEY_PREFIX},
+            session::check_session::check_session,
+            totp::{
+                has_second_factor::has_second_factor, is_admin_2fa_required::is_admin_2fa_required,
+            },
+        },
+    },
+    AppState,
+};
+
+// HttpOnly cookie set by /login for the dashboard
+pub const TOKEN_COOKIE: &str = "watchtower_token";
+
+// User authenticated by a valid user token or API key
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: String,
+    // Empty for API keys
+    pub session_id: String,
+    pub api_key_id: Option<String>,
+    // Scopes of the API key, intersected with the roles of its owner
+    pub scopes: Option<Vec<RoleGrant>>,
+    // Address of the client, X-Forwarded-For is kept apart since it can be forged
+    pub ip: Option<String>,
+    pub forwarded_for: Option<String>,
+}
+
+// User with the global admin role (within the scopes of their API key)
+#[derive(Debug, Clone)]
+pub struct AdminUser(pub AuthUser);
+
+impl Deref for AdminUser {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+// User authenticated by a user token, API keys can't manage the account they belong to
+#[derive(Debug, Clone)]
+pub struct SessionUser(pub AuthUser);
+
+impl Deref for SessionUser {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &AuthUser {
+        &self.0
+    }
+}
+
+pub struct AuthError {
+    status: StatusCode,
+    message: &'static str,
+    error_code: &'static str,
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": self.message,
+            "error_code": self.error_code
+        });
+        (self.status, Json(json_response)).into_response()
+    }
+}
+
+// The Authorization header comes first, then the cookie and the legacy body or query field
+fn get_token(parts: &Parts) -> Option<String> {
+    let bearer_token = get_bearer_token(&parts.headers);
+    if !bearer_token.is_empty() {
+        return Some(bearer_token);
+    }
+    if let Some(cookie) = CookieJar::from_headers(&parts.headers).get(TOKEN_COOKIE) {
+        return Some(cookie.value().to_string());
+    }
+    parts
+        .extensions
+        .get::<LegacyToken>()
+        .map(|legacy_token| legacy_token.0.clone())
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|connect_info| connect_info.0.ip().to_string());
+        let forwarded_for = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let Some(token) = get_token(parts) else {
+            return Err(AuthError {
+                status: StatusCode::UNAUTHORIZED,
+                message: "Missing token",
+                error_code: "missing_token",
+            });
+        };
+
+        if token.starts_with(API_KEY_PREFIX) {
+            let Some(owner) = check_api_key(&app_state.db, &token).await else {
+                return Err(AuthError {
+                    status: StatusCode::UNAUTHORIZED,
+                    message: "Invalid API key, revoked or expired",
+                    error_code: "invalid_api_key",
+                });
+            };
+            return Ok(AuthUser {
+                user_id: owner.user_id,
+                session_id: String::new(),
+                api_key_id: Some(owner.api_key_id),
+                scopes: Some(owner.scopes),
+                ip,
+                forwarded_for,
+            });
+        }
+
+        if !check_auth_token(app_state.clone(), token.clone()) {
+            return Err(AuthError {
+                status: StatusCode::UNAUTHORIZED,
+                message: "Invalid token or token expired",
+                error_code: "invalid_token",
+            });
+        }
+
+        // Logged out, revoked or issued before sessions existed
+        let token_data = get_token_data(app_state.clone(), token);
+        if !check_session(&app_state.db, &token_data.user_id, &token_data.jti).await {
+            return Err(AuthError {
+                status: StatusCode::UNAUTHORIZED,
+                message: "Session revoked or expired",
+                error_code: "invalid_session",
+            });
+        }
+
+        Ok(AuthUser {
+            user_id: token_data.user_id,
+            session_id: token_data.jti,
+            api_key_id: None,
+            scopes: None,
+            ip,
+            forwarded_for,
+        })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AdminUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, app_state).await?;
+
+        if !has_role(app_state.clone(), &user, Role::Admin, None).await {
+            return Err(AuthError {
+                status: StatusCode::FORBIDDEN,
+                message: "You don't have administrator permission",
+                error_code: "permission_denied",
+            });
+        }
+
+        if is_admin_2fa_required() && !has_second_factor(&app_state.db, &user.user_id).await {
+            return Err(AuthError {
+                status: StatusCode::FORBIDDEN,
+                message: "Two-factor authentication is required for administrators",
+                error_code: "two_factor_required",
+            });
+        }
+
+        Ok(AdminUser(user))
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for SessionUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, app_state).await?;
+
+        if user.api_key_id.is_some() {
+            return Err(AuthError {
+                status: StatusCode::FORBIDDEN,
+                message: "This endpoint can't be used with an API key",
+                error_code: "api_key_not_allowed",
+            });
+        }
+
+        Ok(SessionUser(user))
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{header, Request},
+    middleware::Next,
+    response::Response,
+};
+
+// Bodies bigger than this are never buffered to look for a token
+const MAX_LEGACY_BODY_SIZE: u64 = 1024 * 1024;
+
+// Token sent the old way, in the `token` field of the JSON body or of the query string
+#[derive(Debug, Clone)]
+pub struct LegacyToken(pub String);
+
+// Kept while the dashboard moves to the Authorization header and the cookie
+pub async fn extract_legacy_token(request: Request<Body>, next: Next<Body>) -> Response {
+    if request.headers().contains_key(header::AUTHORIZATION) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let query_token = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+        .ok()
+        .and_then(|Query(mut query)| query.remove("token"));
+    if let Some(token) = query_token {
+        parts.extensions.insert(LegacyToken(token));
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let small_body = parts
+        .headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .is_some_and(|length| length <= MAX_LEGACY_BODY_SIZE);
+    if !is_json || !small_body {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    // The body is read once here and handed back to the handler as is
+    let Ok(bytes) = hyper::body::to_bytes(body).await else {
+        return next.run(Request::from_parts(parts, Body::empty())).await;
+    };
+    let body_token = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|body| body.get("token")?.as_str().map(String::from));
+    if let Some(token) = body_token {
+        parts.extensions.insert(LegacyToken(token));
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
@@ -0,0 +1,3 @@
+pub mod extractors;
+pub mod legacy_token;
+pub mod tokens;
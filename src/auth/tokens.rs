@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use axum::http::{header, HeaderMap};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use jsonwebtoken::{encode, EncodingKey, Header};
+
+use crate::{
+    auth::extractors::TOKEN_COOKIE, config::Config, structs,
+    utils::user::session::create_session::create_session, AppState,
+};
+
+// HttpOnly cookie holding the refresh token, only sent to /refresh
+pub const REFRESH_COOKIE: &str = "watchtower_refresh_token";
+const REFRESH_COOKIE_PATH: &str = "/refresh";
+
+// Signs a short-lived access token for the session and sets both cookies,
+// `refresh_expires_at` is the end of the session in milliseconds
+pub fn issue_tokens(
+    config: &Config,
+    jar: CookieJar,
+    user_id: String,
+    session_id: String,
+    refresh_token: String,
+    refresh_expires_at: i64,
+) -> (CookieJar, serde_json::Value) {
+    let access_max_age = config.jwt.access_token_max_age_secs;
+    let date = chrono::Utc::now() + chrono::Duration::seconds(access_max_age);
+
+    let claims = structs::JwtUserClaims {
+        exp: date.timestamp() as usize,
+        user_id,
+        jti: session_id,
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt.user_secret.as_ref()),
+    )
+    .unwrap();
+
+    // The dashboard can rely on the HttpOnly cookies instead of storing the tokens
+    let access_cookie = Cookie::build(TOKEN_COOKIE, token.clone())
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Strict)
+        .secure(config.jwt.secure_cookie)
+        .max_age(time::Duration::seconds(access_max_age))
+        .finish();
+
+    let refresh_max_age = (refresh_expires_at - chrono::Utc::now().timestamp_millis()) / 1000;
+    let refresh_cookie = Cookie::build(REFRESH_COOKIE, refresh_token.clone())
+        .path(REFRESH_COOKIE_PATH)
+        .http_only(true)
+        .same_site(SameSite::Strict)
+        .secure(config.jwt.secure_cookie)
+        .max_age(time::Duration::seconds(refresh_max_age))
+        .finish();
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "token": token,
+        "max_age": access_max_age,
+        "refresh_token": refresh_token,
+        "refresh_max_age": refresh_max_age,
+    });
+
+    (jar.add(access_cookie).add(refresh_cookie), json_response)
+}
+
+// Opens a session for a fully authenticated user
+pub async fn start_session(
+    app_state: &Arc<AppState>,
+    jar: CookieJar,
+    headers: &HeaderMap,
+    user_id: String,
+) -> (CookieJar, serde_json::Value) {
+    // The session lasts as long as its refresh tokens
+    let date =
+        chrono::Utc::now() + chrono::Duration::days(app_state.conf.jwt.refresh_token_max_age_days);
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let (session_id, refresh_token) =
+        create_session(&app_state.db, &user_id, date, user_agent).await;
+
+    issue_tokens(
+        &app_state.conf,
+        jar,
+        user_id,
+        session_id,
+        refresh_token,
+        date.timestamp_millis(),
+    )
+}
+
+pub fn clear_tokens(jar: CookieJar) -> CookieJar {
+    jar.remove(Cookie::build(TOKEN_COOKIE, "").path("/").finish())
+        .remove(
+            Cookie::build(REFRESH_COOKIE, "")
+                .path(REFRESH_COOKIE_PATH)
+                .finish(),
+        )
+}
@@ -1,7 +1,10 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 
+use crate::structs::{Role, RoleScope};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Database {
     pub url: String,
@@ -12,14 +15,88 @@ pub struct Database {
 pub struct JWT {
     pub user_secret: String,
     pub service_secret: String,
-    pub user_max_age: i32,
+    // Lifetime of access tokens, in seconds
+    #[serde(default = "default_access_token_max_age_secs")]
+    pub access_token_max_age_secs: i64,
+    // Lifetime of a login (its session and refresh tokens), in days
+    #[serde(default = "default_refresh_token_max_age_days")]
+    pub refresh_token_max_age_days: i64,
+    // Only send the token cookie over HTTPS
+    #[serde(default)]
+    pub secure_cookie: bool,
+}
+
+fn default_access_token_max_age_secs() -> i64 {
+    15 * 60
+}
+
+fn default_refresh_token_max_age_days() -> i64 {
+    30
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Security {
+    // Salt of the hashes created before per-user salts, they are upgraded on the next login
+    #[serde(default)]
     pub password_salt: String,
     pub root_user_password: String,
     pub auto_update_root_user: bool,
+    #[serde(default)]
+    pub argon2: Argon2Params,
+    #[serde(default)]
+    pub login_protection: LoginProtection,
+}
+
+// Failed logins are counted per username and per IP
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoginProtection {
+    // Failures before the username or IP is locked
+    pub max_attempts: u32,
+    pub lockout_minutes: i64,
+    // Delay after the first failure, doubled on each following one
+    pub backoff_base_secs: i64,
+    pub backoff_max_secs: i64,
+    // Failures older than this are forgotten
+    pub window_minutes: i64,
+    // Only when running behind a reverse proxy that sets X-Forwarded-For
+    pub trust_forwarded_for: bool,
+    // Number of trusted proxies in front of the server, each appends an address to the header
+    pub trusted_proxy_hops: usize,
+}
+
+impl Default for LoginProtection {
+    fn default() -> Self {
+        LoginProtection {
+            max_attempts: 10,
+            lockout_minutes: 15,
+            backoff_base_secs: 1,
+            backoff_max_secs: 60,
+            window_minutes: 15,
+            trust_forwarded_for: false,
+            trusted_proxy_hops: 1,
+        }
+    }
+}
+
+// Argon2id parameters of new hashes, existing hashes are upgraded on the next login
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Argon2Params {
+    // Memory in KiB
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Argon2Params {
+            memory_cost: 19 * 1024,
+            time_cost: 2,
+            parallelism: 1,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -27,12 +104,177 @@ pub struct Connections {
     pub telegram_token: String,
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Ingestion {
+    // Types with an importance above this value are never sampled nor dropped
+    pub max_sampled_importance: i32,
+    // Default limits of every service, 0 means unlimited
+    pub rate_limit_per_second: f64,
+    pub rate_limit_burst: f64,
+    pub daily_quota: i64,
+    // Maximum size in bytes of an add_messages body once decompressed, 0 means 2 MB
+    pub max_body_size: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Syslog {
+    pub enabled: bool,
+    // Addresses to listen on, e.g. "0.0.0.0:514", a missing one disables the transport
+    pub udp_address: Option<String>,
+    pub tcp_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Grpc {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for Grpc {
+    fn default() -> Self {
+        Grpc {
+            enabled: false,
+            address: "0.0.0.0:50051".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SinkOutput {
+    // POST of a JSON array of logs
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    // Bulk API of Elasticsearch or OpenSearch, `url` is the cluster url
+    Elasticsearch {
+        url: String,
+        index: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    // One JSON log per line appended to the file
+    File {
+        path: String,
+    },
+}
+
+fn default_sink_batch_size() -> usize {
+    100
+}
+
+fn default_sink_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_sink_queue_size() -> usize {
+    10000
+}
+
+fn default_sink_max_retries() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sink {
+    pub name: String,
+    #[serde(flatten)]
+    pub output: SinkOutput,
+    // Only forward logs of these services and types, empty means all of them
+    #[serde(default)]
+    pub app_ids: Vec<String>,
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default = "default_sink_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_sink_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    // Logs waiting to be forwarded, new logs are dropped once it is full
+    #[serde(default = "default_sink_queue_size")]
+    pub queue_size: usize,
+    #[serde(default = "default_sink_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_oidc_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+fn default_oidc_groups_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_oidc_post_login_redirect() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Oidc {
+    // Discovery document is read from <issuer_url>/.well-known/openid-configuration
+    pub issuer_url: String,
+    pub client_id: String,
+    // Public clients only rely on PKCE
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    // URL of /oidc/callback as registered at the provider
+    pub redirect_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default = "default_oidc_username_claim")]
+    pub username_claim: String,
+    #[serde(default = "default_oidc_groups_claim")]
+    pub groups_claim: String,
+    // Where the browser is sent once logged in
+    #[serde(default = "default_oidc_post_login_redirect")]
+    pub post_login_redirect: String,
+    // Only root can log in with a password when set
+    #[serde(default)]
+    pub disable_local_login: bool,
+    // When not empty, the roles of single sign-on users are replaced on each login
+    #[serde(default)]
+    pub group_roles: Vec<OidcGroupRole>,
+}
+
+// Role granted to the members of a group of the provider
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcGroupRole {
+    pub group: String,
+    pub role: Role,
+    pub scope: RoleScope,
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub database: Database,
     pub jwt: JWT,
     pub security: Security,
     pub connections: Connections,
+    #[serde(default)]
+    pub ingestion: Ingestion,
+    #[serde(default)]
+    pub syslog: Syslog,
+    #[serde(default)]
+    pub grpc: Grpc,
+    #[serde(default)]
+    pub sinks: Vec<Sink>,
+    // Single sign-on is disabled when not set
+    #[serde(default)]
+    pub oidc: Option<Oidc>,
 }
 
 pub fn load() -> Config {
@@ -1,3 +1,9 @@
+use mongodb::{
+    bson::{doc, Document},
+    options::IndexOptions,
+    Collection, IndexModel,
+};
+
 use crate::config::Config;
 
 pub async fn config(config: Config, client: mongodb::Client) -> bool {
@@ -43,7 +49,167 @@ pub async fn config(config: Config, client: mongodb::Client) -> bool {
         db.create_collection("databases", None)
             .await
             .expect("Failed to create collection: databases");
+        db.create_collection("issues", None)
+            .await
+            .expect("Failed to create collection: issues");
     }
+    // Indexes (no-op when they already exist):
+    let issues: Collection<Document> = db.collection("issues");
+    issues
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "fingerprint": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: issues.fingerprint");
+    let logs: Collection<Document> = db.collection("logs");
+    logs.create_index(
+        IndexModel::builder()
+            .keys(doc! { "fingerprint": 1, "timestamp": -1 })
+            .build(),
+        None,
+    )
+    .await
+    .expect("Failed to create index: logs.fingerprint");
+    logs.create_index(
+        IndexModel::builder()
+            .keys(doc! { "trace_id": 1, "timestamp": 1 })
+            .options(IndexOptions::builder().sparse(true).build())
+            .build(),
+        None,
+    )
+    .await
+    .expect("Failed to create index: logs.trace_id");
+    // One stats document per service and day, ingestion quotas rely on it
+    let ingestion_stats: Collection<Document> = db.collection("ingestion_stats");
+    ingestion_stats
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "app_id": 1, "day": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: ingestion_stats.app_id_day");
+    // Sessions are removed by MongoDB once expired
+    let sessions: Collection<Document> = db.collection("sessions");
+    sessions
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "expires_at": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(std::time::Duration::from_secs(0))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: sessions.expires_at");
+    sessions
+        .create_index(
+            IndexModel::builder().keys(doc! { "user_id": 1 }).build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: sessions.user_id");
+    let api_keys: Collection<Document> = db.collection("api_keys");
+    api_keys
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "key_hash": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: api_keys.key_hash");
+    api_keys
+        .create_index(
+            IndexModel::builder().keys(doc! { "user_id": 1 }).build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: api_keys.user_id");
+    let audit_log: Collection<Document> = db.collection("audit_log");
+    audit_log
+        .create_index(
+            IndexModel::builder().keys(doc! { "timestamp": -1 }).build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: audit_log.timestamp");
+    audit_log
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "actor.user_id": 1, "timestamp": -1 })
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: audit_log.actor");
+    audit_log
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "target_type": 1, "target_id": 1, "timestamp": -1 })
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: audit_log.target");
+    // Failed login counters are removed by MongoDB once expired
+    let login_attempts: Collection<Document> = db.collection("login_attempts");
+    login_attempts
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "expires_at": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(std::time::Duration::from_secs(0))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: login_attempts.expires_at");
+    // Single sign-on logins in progress are removed by MongoDB once expired
+    let oidc_states: Collection<Document> = db.collection("oidc_states");
+    oidc_states
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "expires_at": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(std::time::Duration::from_secs(0))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: oidc_states.expires_at");
+    // Pending second login steps are removed by MongoDB once expired
+    let mfa_challenges: Collection<Document> = db.collection("mfa_challenges");
+    mfa_challenges
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "expires_at": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(std::time::Duration::from_secs(0))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await
+        .expect("Failed to create index: mfa_challenges.expires_at");
     // Print the collections in our database:
     println!("📌 Collections:");
     for collection_name in db.list_collection_names(None).await.unwrap() {
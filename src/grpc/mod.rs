@@ -0,0 +1,4 @@
+pub mod proto {
+    tonic::include_proto!("watchtower.v1");
+}
+pub mod server;
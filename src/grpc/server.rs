@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::Response};
+use tonic::{transport::Server, Request, Status, Streaming};
+
+use crate::{
+    grpc::proto::{
+        log_ingestion_server::{LogIngestion, LogIngestionServer},
+        AddLogsRequest, AddLogsResponse,
+    },
+    structs,
+    utils::logs_service_side::add_logs::add_logs,
+    AppState,
+};
+
+pub struct LogIngestionService {
+    app_state: Arc<AppState>,
+}
+
+pub async fn start_grpc(app_state: Arc<AppState>) {
+    let address = app_state.conf.grpc.address.clone();
+    let service = LogIngestionService {
+        app_state: app_state.clone(),
+    };
+    println!("📡 gRPC server listening on {}", address);
+    Server::builder()
+        .add_service(LogIngestionServer::new(service))
+        .serve(address.parse().expect("Invalid gRPC address"))
+        .await
+        .expect("Failed to start gRPC server");
+}
+
+fn get_token<T>(request: &Request<T>) -> String {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .to_string()
+}
+
+// Errors of the JSON endpoints are HTTP responses, map them to gRPC statuses
+async fn to_status(response: Response) -> Status {
+    let code = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .unwrap_or_default();
+    let message = String::from_utf8_lossy(&body).to_string();
+    match code {
+        StatusCode::UNAUTHORIZED => Status::unauthenticated(message),
+        StatusCode::TOO_MANY_REQUESTS => Status::resource_exhausted(message),
+        StatusCode::BAD_REQUEST => Status::invalid_argument(message),
+        _ => Status::internal(message),
+    }
+}
+
+impl LogIngestionService {
+    async fn add_batch(&self, token: String, batch: AddLogsRequest) -> Result<usize, Status> {
+        let mut logs = Vec::new();
+        for log in batch.logs {
+            let fields =
+                match log.fields {
+                    Some(fields) => Some(serde_json::from_str(&fields).map_err(|err| {
+                        Status::invalid_argument(format!("Invalid fields: {}", err))
+                    })?),
+                    None => None,
+                };
+            logs.push(structs::Log {
+                _id: None,
+                app_id: None,
+                type_: log.r#type,
+                message: log.message,
+                timestamp: log.timestamp,
+                fields,
+                trace_id: log.trace_id,
+                span_id: log.span_id,
+                parent_span_id: log.parent_span_id,
+            });
+        }
+
+        match add_logs(self.app_state.clone(), token, batch.app_id, logs).await {
+            Ok(added) => Ok(added),
+            Err(response) => Err(to_status(response).await),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl LogIngestion for LogIngestionService {
+    async fn add_logs(
+        &self,
+        request: Request<AddLogsRequest>,
+    ) -> Result<tonic::Response<AddLogsResponse>, Status> {
+        let token = get_token(&request);
+        let added = self.add_batch(token, request.into_inner()).await?;
+        Ok(tonic::Response::new(AddLogsResponse {
+            added: added as u64,
+        }))
+    }
+
+    async fn stream_logs(
+        &self,
+        request: Request<Streaming<AddLogsRequest>>,
+    ) -> Result<tonic::Response<AddLogsResponse>, Status> {
+        let token = get_token(&request);
+        let mut stream = request.into_inner();
+
+        let mut added = 0;
+        while let Some(batch) = stream.message().await? {
+            added += self.add_batch(token.clone(), batch).await?;
+        }
+        Ok(tonic::Response::new(AddLogsResponse {
+            added: added as u64,
+        }))
+    }
+}
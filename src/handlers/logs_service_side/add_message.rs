@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use crate::{
+    structs,
     utils::logs_service_side::{
         add_message::add_message, check_service_token::check_service_token,
-        get_service_token_data::get_service_token_data,
+        get_service_token_data::get_service_token_data, rate_limit::check_ingestion_limits,
     },
     AppState,
 };
@@ -19,6 +20,9 @@ pub struct LogInput {
     r#type: Option<String>,
     message: String,
     timestamp: Option<i64>,
+    trace_id: Option<String>,
+    span_id: Option<String>,
+    parent_span_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -56,13 +60,21 @@ pub async fn add_message_handler(
             .into_response());
     }
 
+    check_ingestion_limits(app_state.clone(), &token_app_id, 1).await?;
+
     Ok(add_message(
         app_state.clone(),
-        log._id,
-        log.app_id,
-        log.r#type,
-        log.message,
-        log.timestamp,
+        structs::Log {
+            _id: log._id,
+            app_id: log.app_id,
+            type_: log.r#type,
+            message: log.message,
+            timestamp: log.timestamp,
+            fields: None,
+            trace_id: log.trace_id,
+            span_id: log.span_id,
+            parent_span_id: log.parent_span_id,
+        },
     )
     .await)
 }
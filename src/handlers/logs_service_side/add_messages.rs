@@ -1,81 +1,187 @@
-use std::sync::Arc;
+use std::{pin::Pin, sync::Arc};
 
 use crate::{
+    structs,
     utils::logs_service_side::{
-        add_message::add_message, check_service_token::check_service_token,
-        get_service_token_data::get_service_token_data,
+        add_logs::add_logs,
+        get_bearer_token::get_bearer_token,
+        get_body_reader::{get_body_reader, DEFAULT_MAX_BODY_SIZE},
+        get_token_app_id::get_token_app_id,
     },
     AppState,
 };
-use axum::{extract::State, response::IntoResponse, Json};
-use mongodb::bson::doc;
+use axum::{
+    extract::{BodyStream, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
 use reqwest::StatusCode;
-use serde::Deserialize;
-
-#[derive(Deserialize)]
-pub struct LogInput {
-    _id: Option<String>,
-    r#type: Option<String>,
-    message: String,
-    timestamp: Option<i64>,
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+use watchtower_types::{AddMessagesInput, AddMessagesSummary, LineError, LogInput};
+
+// NDJSON bodies are inserted by chunks of this many logs as they are received
+const NDJSON_CHUNK_SIZE: usize = 500;
+// Only the first invalid lines are detailed in the summary
+const MAX_REPORTED_ERRORS: usize = 100;
+
+fn to_log(log: LogInput) -> structs::Log {
+    structs::Log {
+        _id: log._id,
+        app_id: None,
+        type_: log.r#type,
+        message: log.message,
+        timestamp: log.timestamp,
+        fields: log.fields,
+        trace_id: log.trace_id,
+        span_id: log.span_id,
+        parent_span_id: log.parent_span_id,
+    }
 }
 
-#[derive(Deserialize)]
-pub struct AddMessageInput {
-    token: String,
-    app_id: Option<String>,
-    logs: Vec<LogInput>,
+fn payload_too_large(max_body_size: u64) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("The body exceeds the limit of {} bytes", max_body_size),
+    )
+        .into_response()
 }
 
+// Bodies can be gzip compressed (`Content-Encoding: gzip`) and either a JSON object with all the
+// logs or, with `Content-Type: application/x-ndjson`, one log per line authenticated by a bearer token
 pub async fn add_messages_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<AddMessageInput>,
-) -> impl IntoResponse {
-    let token = body.token;
-
-    let valid = check_service_token(app_state.clone(), token.clone()).await;
+    headers: HeaderMap,
+    body: BodyStream,
+) -> Result<Response, Response> {
+    let max_body_size = match app_state.conf.ingestion.max_body_size {
+        0 => DEFAULT_MAX_BODY_SIZE,
+        max_body_size => max_body_size,
+    };
+    let mut reader = get_body_reader(&headers, body, max_body_size)
+        .map_err(|err| (StatusCode::UNSUPPORTED_MEDIA_TYPE, err).into_response())?;
 
-    if !valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token or token expired").into_response());
+    let ndjson = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-ndjson"));
+    if ndjson {
+        // Fail before reading the body, even when it has no logs
+        let token = get_bearer_token(&headers);
+        get_token_app_id(app_state.clone(), token.clone()).await?;
+        return add_ndjson_messages(app_state, token, reader, max_body_size).await;
     }
 
-    let logs = body.logs;
-    let token_data = Some(get_service_token_data(app_state.clone(), token.clone()));
-    let token_app_id = token_data.unwrap().unwrap().app_id;
-    let app_id = body.app_id.clone().unwrap();
-
-    if token_app_id != app_id {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            format!(
-                "You specified a wrong app_id. You specified {} but your token contains {}",
-                app_id, token_app_id
-            ),
-        )
-            .into_response());
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).await.map_err(|err| {
+        (StatusCode::BAD_REQUEST, format!("Invalid body: {}", err)).into_response()
+    })?;
+    if body.len() as u64 > max_body_size {
+        return Err(payload_too_large(max_body_size));
     }
+    let body: AddMessagesInput = serde_json::from_slice(&body).map_err(|err| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Invalid JSON body: {}", err),
+        )
+            .into_response()
+    })?;
 
-    let logs_len = logs.len();
+    let logs = body.logs.into_iter().map(to_log).collect();
+    let logs_len = add_logs(app_state, body.token, body.app_id, logs).await?;
 
-    for log in logs {
-        let res = add_message(
-            app_state.clone(),
-            log._id,
-            Some(app_id.clone()),
-            log.r#type,
-            log.message,
-            log.timestamp,
-        )
-        .await;
+    Ok((
+        StatusCode::OK,
+        format!("Successfully added {} logs to the database", logs_len),
+    )
+        .into_response())
+}
 
-        if res.is_err() {
-            return Ok(res);
+async fn add_ndjson_messages(
+    app_state: Arc<AppState>,
+    token: String,
+    reader: Pin<Box<dyn AsyncBufRead + Send>>,
+    max_body_size: u64,
+) -> Result<Response, Response> {
+    let mut lines = reader.lines();
+    let mut read = 0;
+    let mut line_number = 0;
+    let mut chunk = Vec::new();
+    let mut added = 0;
+    let mut errors = Vec::new();
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(line) => line,
+            Err(err) => {
+                let response =
+                    (StatusCode::BAD_REQUEST, format!("Invalid body: {}", err)).into_response();
+                return Err(with_summary(response, added, &errors).await);
+            }
+        };
+
+        if let Some(line) = &line {
+            line_number += 1;
+            read += line.len() as u64 + 1;
+            if read > max_body_size {
+                return Err(with_summary(payload_too_large(max_body_size), added, &errors).await);
+            }
+            if !line.trim().is_empty() {
+                match serde_json::from_str::<LogInput>(line) {
+                    Ok(log) => chunk.push(to_log(log)),
+                    Err(err) => errors.push((line_number, err.to_string())),
+                }
+            }
+        }
+
+        if chunk.len() >= NDJSON_CHUNK_SIZE || (line.is_none() && !chunk.is_empty()) {
+            let logs = std::mem::take(&mut chunk);
+            match add_logs(app_state.clone(), token.clone(), None, logs).await {
+                Ok(logs_len) => added += logs_len,
+                Err(response) => return Err(with_summary(response, added, &errors).await),
+            }
+        }
+
+        if line.is_none() {
+            break;
         }
     }
 
-    return Err((
-        StatusCode::OK,
-        format!("Successfully added {} logs to the database", logs_len),
+    Ok(Json(summary("success", None, added, &errors)).into_response())
+}
+
+fn summary(
+    status: &str,
+    message: Option<String>,
+    added: usize,
+    errors: &[(usize, String)],
+) -> AddMessagesSummary {
+    AddMessagesSummary {
+        status: status.to_string(),
+        message,
+        added,
+        failed: errors.len(),
+        errors: errors
+            .iter()
+            .take(MAX_REPORTED_ERRORS)
+            .map(|(line, error)| LineError {
+                line: *line,
+                error: error.clone(),
+            })
+            .collect(),
+    }
+}
+
+// Logs of the previous chunks are already inserted when an error happens, tell the client how many
+async fn with_summary(response: Response, added: usize, errors: &[(usize, String)]) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let message = hyper::body::to_bytes(body).await.unwrap_or_default();
+    let message = String::from_utf8_lossy(&message).to_string();
+    parts.headers.remove(header::CONTENT_TYPE);
+    (
+        parts.status,
+        parts.headers,
+        Json(summary("error", Some(message), added, errors)),
     )
-        .into_response());
+        .into_response()
 }
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    loki::push::{decode_json, decode_protobuf, to_logs},
+    utils::logs_service_side::{
+        add_message::add_message, get_bearer_token::get_bearer_token,
+        get_token_app_id::get_token_app_id, rate_limit::check_ingestion_limits,
+    },
+    AppState,
+};
+
+pub async fn loki_push_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, Response> {
+    let token = get_bearer_token(&headers);
+    let app_id = get_token_app_id(app_state.clone(), token).await?;
+
+    // Promtail sends snappy compressed protobuf, JSON is used by most other clients
+    let json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let streams = if json {
+        decode_json(&body)
+    } else {
+        decode_protobuf(&body)
+    };
+    let streams = streams.map_err(|err| (StatusCode::BAD_REQUEST, err).into_response())?;
+
+    let logs =
+        to_logs(&app_id, streams).map_err(|err| (StatusCode::UNAUTHORIZED, err).into_response())?;
+    check_ingestion_limits(app_state.clone(), &app_id, logs.len()).await?;
+
+    for log in logs {
+        if let Err(err) = add_message(app_state.clone(), log).await {
+            return Err(err.into_response());
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
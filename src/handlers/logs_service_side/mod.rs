@@ -1,2 +1,5 @@
 pub mod add_message;
 pub mod add_messages;
+pub mod loki_push;
+pub mod otlp_logs;
+pub mod webhook;
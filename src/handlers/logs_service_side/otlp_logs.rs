@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use prost::Message;
+
+use crate::{
+    otlp::{
+        logs::to_logs,
+        proto::{ExportLogsServiceRequest, ExportLogsServiceResponse},
+    },
+    utils::logs_service_side::{
+        add_message::add_message, get_bearer_token::get_bearer_token,
+        get_token_app_id::get_token_app_id, rate_limit::check_ingestion_limits,
+    },
+    AppState,
+};
+
+pub async fn otlp_logs_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, Response> {
+    let token = get_bearer_token(&headers);
+    let app_id = get_token_app_id(app_state.clone(), token).await?;
+
+    let json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let request = if json {
+        serde_json::from_slice::<ExportLogsServiceRequest>(&body)
+            .map_err(|err| format!("Invalid OTLP JSON request: {}", err))
+    } else {
+        ExportLogsServiceRequest::decode(body)
+            .map_err(|err| format!("Invalid OTLP protobuf request: {}", err))
+    };
+    let request = request.map_err(|err| (StatusCode::BAD_REQUEST, err).into_response())?;
+
+    let logs = to_logs(&app_id, request);
+    check_ingestion_limits(app_state.clone(), &app_id, logs.len()).await?;
+
+    for log in logs {
+        if let Err(err) = add_message(app_state.clone(), log).await {
+            return Err(err.into_response());
+        }
+    }
+
+    // Every record was accepted, so the response has no partial success
+    if json {
+        Ok(Json(serde_json::json!({})).into_response())
+    } else {
+        Ok((
+            [(header::CONTENT_TYPE, "application/x-protobuf")],
+            ExportLogsServiceResponse::default().encode_to_vec(),
+        )
+            .into_response())
+    }
+}
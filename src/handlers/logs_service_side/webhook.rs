@@ -0,0 +1,127 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use hmac::{Hmac, Mac};
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use sha2::Sha256;
+
+use crate::{
+    structs,
+    utils::logs_service_side::{
+        add_message::add_message,
+        rate_limit::check_ingestion_limits,
+        webhook_mapping::{apply_mapping, WebhookMapping},
+    },
+    AppState,
+};
+
+// GitHub style signatures: `X-Hub-Signature-256: sha256=<hex hmac of the body>`
+fn verify_signature(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+// Compare secrets in constant time
+fn secrets_match(expected: &str, secret: &str) -> bool {
+    expected.len() == secret.len()
+        && expected
+            .bytes()
+            .zip(secret.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// Webhooks are called on /webhooks/<id>/<secret> or on /webhooks/<id> with an HMAC signature
+pub async fn webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    Path(params): Path<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, Response> {
+    let not_found = || (StatusCode::NOT_FOUND, "Webhook not found").into_response();
+    let webhook_id = ObjectId::parse_str(&params["webhook_id"]).map_err(|_| not_found())?;
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("webhooks");
+    let webhook = collection
+        .find_one(doc! { "_id": webhook_id }, None)
+        .await
+        .unwrap()
+        .ok_or_else(not_found)?;
+
+    let secret = webhook.get_str("secret").unwrap();
+    let verified = match webhook.get_str("verification").unwrap_or("path") {
+        "hmac" => {
+            let signature_header = webhook
+                .get_str("signature_header")
+                .unwrap_or("X-Hub-Signature-256");
+            headers
+                .get(signature_header)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|signature| verify_signature(secret, signature, &body))
+        }
+        _ => params
+            .get("secret")
+            .is_some_and(|path_secret| secrets_match(secret, path_secret)),
+    };
+    if !verified {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "Invalid webhook secret or signature",
+        )
+            .into_response());
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid JSON payload: {}", err),
+        )
+            .into_response()
+    })?;
+    let mapping: WebhookMapping =
+        mongodb::bson::from_bson(webhook.get("mapping").unwrap().clone()).unwrap();
+    let logs = apply_mapping(&mapping, &payload)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err).into_response())?;
+
+    let app_id = webhook.get_str("app_id").unwrap().to_string();
+    check_ingestion_limits(app_state.clone(), &app_id, logs.len()).await?;
+
+    let logs_len = logs.len();
+    for log in logs {
+        let res = add_message(
+            app_state.clone(),
+            structs::Log {
+                _id: None,
+                app_id: Some(app_id.clone()),
+                type_: log.r#type,
+                message: log.message,
+                timestamp: None,
+                fields: Some(serde_json::Value::Object(log.fields)),
+                trace_id: None,
+                span_id: None,
+                parent_span_id: None,
+            },
+        )
+        .await;
+        if let Err(err) = res {
+            return Err(err.into_response());
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "added": logs_len,
+    })))
+}
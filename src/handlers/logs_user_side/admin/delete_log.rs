@@ -5,61 +5,48 @@ use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct DeleteLogInput {
-    token: String,
     log_id: String,
 }
 
 pub async fn delete_log_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<DeleteLogInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
+    let log_id = body.log_id;
+    let parsed_log_id = mongodb::bson::oid::ObjectId::parse_str(&log_id).unwrap();
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("logs");
+    let log = collection
+        .find_one(doc! { "_id": parsed_log_id }, None)
+        .await
+        .unwrap();
+    let app_id = log
+        .as_ref()
+        .and_then(|log| log.get_str("app_id").ok().map(String::from));
 
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, app_id.as_deref()).await;
 
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the operator role on this service",
             "error_code": "permission_denied"
         });
 
         return Json(json_response);
     }
 
-    let log_id = body.log_id;
-    let parsed_log_id = mongodb::bson::oid::ObjectId::parse_str(&log_id).unwrap();
-
     let date = chrono::Utc::now().timestamp();
 
-    let db = &app_state.db;
-    let collection: mongodb::Collection<Document> = db.collection("logs");
     collection
         .update_one(
             doc! {"_id": parsed_log_id},
@@ -68,6 +55,22 @@ pub async fn delete_log_handler(
         )
         .await
         .unwrap();
+
+    let deleted_log = collection
+        .find_one(doc! { "_id": parsed_log_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &user,
+        "delete_log",
+        "log",
+        Some(&log_id),
+        log.as_ref(),
+        deleted_log.as_ref(),
+    )
+    .await;
+
     return Json(serde_json::json!({
         "status": "success",
     }));
@@ -1,2 +1,3 @@
 pub mod delete_log;
 pub mod regenerate_service_token;
+pub mod resolve_issue;
@@ -6,49 +6,28 @@ use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
 use crate::{
-    structs,
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::{self, Role},
+    utils::{audit_log::audit_log, has_role::has_role},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct RegenerateServiceTokenInput {
-    token: String,
     app_id: String,
 }
 
 pub async fn regenerate_service_token_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<RegenerateServiceTokenInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, Some(&body.app_id)).await;
 
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the operator role on this service",
             "error_code": "permission_denied"
         });
 
@@ -87,13 +66,16 @@ pub async fn regenerate_service_token_handler(
 
     if token_doc.is_none() {
         collection
-            .insert_one(doc! { "app_id": app_id, "expired_date": date - 1 }, None)
+            .insert_one(
+                doc! { "app_id": app_id.clone(), "expired_date": date - 1 },
+                None,
+            )
             .await
             .unwrap();
     } else {
         collection
             .update_one(
-                doc! { "app_id": app_id },
+                doc! { "app_id": app_id.clone() },
                 doc! { "$set": { "expired_date": date - 1 } },
                 None,
             )
@@ -101,6 +83,18 @@ pub async fn regenerate_service_token_handler(
             .unwrap();
     }
 
+    // Tokens issued before `expired_date` are refused
+    audit_log(
+        &app_state,
+        &user,
+        "regenerate_service_token",
+        "service",
+        Some(&app_id),
+        token_doc.as_ref(),
+        Some(&doc! { "app_id": &app_id, "expired_date": date - 1 }),
+    )
+    .await;
+
     return Json(serde_json::json!({
         "status": "success",
         "token": token,
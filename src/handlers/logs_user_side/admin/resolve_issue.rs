@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ResolveIssueInput {
+    issue_id: String,
+}
+
+pub async fn resolve_issue_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<ResolveIssueInput>,
+) -> impl IntoResponse {
+    let Ok(issue_id) = mongodb::bson::oid::ObjectId::parse_str(&body.issue_id) else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Issue not found",
+            "error_code": "issue_not_found"
+        });
+
+        return Json(json_response);
+    };
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("issues");
+    let issue = collection
+        .find_one(doc! { "_id": issue_id }, None)
+        .await
+        .unwrap();
+    let app_id = issue
+        .as_ref()
+        .and_then(|issue| issue.get_str("app_id").ok().map(String::from));
+
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, app_id.as_deref()).await;
+
+    if !has_perm {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "You don't have the operator role on this service",
+            "error_code": "permission_denied"
+        });
+
+        return Json(json_response);
+    }
+
+    let date = chrono::Utc::now().timestamp_millis();
+
+    let res = collection
+        .update_one(
+            doc! { "_id": issue_id },
+            doc! { "$set": { "status": "resolved", "resolved_at": date } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    if res.matched_count == 0 {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Issue not found",
+            "error_code": "issue_not_found"
+        });
+
+        return Json(json_response);
+    }
+
+    let resolved_issue = collection
+        .find_one(doc! { "_id": issue_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &user,
+        "resolve_issue",
+        "issue",
+        Some(&body.issue_id),
+        issue.as_ref(),
+        resolved_issue.as_ref(),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
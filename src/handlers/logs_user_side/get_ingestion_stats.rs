@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AuthUser, structs, utils::get_viewable_apps::get_viewable_apps, AppState,
+};
+
+#[derive(Deserialize)]
+pub struct GetIngestionStatsInput {
+    target_apps: Option<Vec<String>>,
+    // Days formatted as YYYY-MM-DD, both inclusive
+    from_day: Option<String>,
+    to_day: Option<String>,
+}
+
+pub async fn get_ingestion_stats_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<GetIngestionStatsInput>,
+) -> impl IntoResponse {
+    // Only the services the user has a role on
+    let target_apps = get_viewable_apps(app_state.clone(), &user, body.target_apps).await;
+
+    let stats = get_ingestion_stats(app_state, target_apps, body.from_day, body.to_day)
+        .await
+        .unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "stats": stats,
+    }))
+}
+
+async fn get_ingestion_stats(
+    app_state: Arc<AppState>,
+    app_ids: Option<Vec<String>>,
+    from_day: Option<String>,
+    to_day: Option<String>,
+) -> Result<Vec<structs::IngestionStats>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("ingestion_stats");
+
+    let mut filter = doc! {};
+    if let Some(app_ids) = app_ids {
+        filter.insert("app_id", doc! { "$in": app_ids });
+    }
+    let mut day_filter = doc! {};
+    if let Some(from_day) = from_day {
+        day_filter.insert("$gte", from_day);
+    }
+    if let Some(to_day) = to_day {
+        day_filter.insert("$lte", to_day);
+    }
+    if !day_filter.is_empty() {
+        filter.insert("day", day_filter);
+    }
+
+    let mut cursor = collection
+        .find(
+            filter,
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "day": -1 })
+                .build(),
+        )
+        .await?;
+
+    let mut result: Vec<structs::IngestionStats> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let stats = structs::IngestionStats {
+            app_id: doc.get_str("app_id").unwrap().to_string(),
+            day: doc.get_str("day").unwrap().to_string(),
+            sampled: doc.get_i64("sampled").unwrap_or(0),
+            dropped: doc.get_i64("dropped").unwrap_or(0),
+        };
+        result.push(stats);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AuthUser,
+    structs::{self, Role},
+    utils::has_role::has_role,
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct GetIssueLogsInput {
+    issue_id: String,
+    page_id: u64,
+    page_size: u64,
+}
+
+pub async fn get_issue_logs_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<GetIssueLogsInput>,
+) -> impl IntoResponse {
+    let Ok(issue_id) = mongodb::bson::oid::ObjectId::parse_str(&body.issue_id) else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Issue not found",
+            "error_code": "issue_not_found"
+        });
+
+        return Json(json_response);
+    };
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("issues");
+    let issue = collection
+        .find_one(doc! { "_id": issue_id }, None)
+        .await
+        .unwrap();
+
+    if issue.is_none() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Issue not found",
+            "error_code": "issue_not_found"
+        });
+
+        return Json(json_response);
+    }
+
+    let issue = issue.unwrap();
+    let app_id = issue.get_str("app_id").unwrap();
+    if !has_role(app_state.clone(), &user, Role::Viewer, Some(app_id)).await {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "You don't have the viewer role on this service",
+            "error_code": "permission_denied"
+        });
+
+        return Json(json_response);
+    }
+
+    let fingerprint = issue.get_str("fingerprint").unwrap().to_string();
+    let result = get_issue_logs(app_state.clone(), fingerprint, body.page_id, body.page_size)
+        .await
+        .unwrap();
+    let logs: Vec<structs::Log> = result.1;
+    let count = result.0;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "logs": logs,
+        "next_elements": count,
+    }))
+}
+
+async fn get_issue_logs(
+    app_state: Arc<AppState>,
+    fingerprint: String,
+    page_id: u64,
+    page_size: u64,
+) -> Result<(u64, Vec<structs::Log>), mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("logs");
+
+    let skip: u64 = page_size * page_id;
+    let filter = doc! {
+        "fingerprint": fingerprint,
+        "deleted": {
+            "$exists": false
+        }
+    };
+    let mut cursor = collection
+        .find(
+            filter.clone(),
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "timestamp": -1 })
+                .skip(skip)
+                .limit(page_size as i64)
+                .build(),
+        )
+        .await?;
+
+    let mut result: Vec<structs::Log> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let message = doc.get("message").unwrap().unwrap().as_str().unwrap();
+        let app_id = doc.get("app_id").unwrap().unwrap().as_str().unwrap();
+        let timestamp = doc.get("timestamp").unwrap().unwrap().as_i64().unwrap();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let type_ = doc.get("type_").unwrap().unwrap().as_str().unwrap();
+        let fields = doc.get_document("fields").ok().map(|fields| {
+            mongodb::bson::Bson::Document(Document::try_from(fields).unwrap())
+                .into_relaxed_extjson()
+        });
+        let log = structs::Log {
+            _id: Some(_id.to_hex()),
+            app_id: Some(app_id.to_string()),
+            type_: Some(type_.to_string()),
+            message: message.to_string(),
+            timestamp: Some(timestamp),
+            fields,
+            trace_id: doc.get_str("trace_id").ok().map(|id| id.to_string()),
+            span_id: doc.get_str("span_id").ok().map(|id| id.to_string()),
+            parent_span_id: doc.get_str("parent_span_id").ok().map(|id| id.to_string()),
+        };
+        result.push(log);
+    }
+
+    let count =
+        (collection.count_documents(filter, None).await?) as i64 - skip as i64 - page_size as i64;
+    let count = if count < 0 { 0 } else { count } as u64;
+
+    Ok((count, result))
+}
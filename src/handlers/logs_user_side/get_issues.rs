@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AuthUser, structs, utils::get_viewable_apps::get_viewable_apps, AppState,
+};
+
+#[derive(Deserialize)]
+pub struct GetIssuesInput {
+    target_apps: Option<Vec<String>>,
+    status: Option<String>,
+    page_id: u64,
+    page_size: u64,
+}
+
+pub async fn get_issues_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<GetIssuesInput>,
+) -> impl IntoResponse {
+    // Only the services the user has a role on
+    let target_apps = get_viewable_apps(app_state.clone(), &user, body.target_apps).await;
+
+    let result = get_issues(
+        app_state.clone(),
+        target_apps,
+        body.status,
+        body.page_id,
+        body.page_size,
+    )
+    .await
+    .unwrap();
+    let issues: Vec<structs::Issue> = result.1;
+    let count = result.0;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "issues": issues,
+        "next_elements": count,
+    }))
+}
+
+async fn get_issues(
+    app_state: Arc<AppState>,
+    app_ids: Option<Vec<String>>,
+    status: Option<String>,
+    page_id: u64,
+    page_size: u64,
+) -> Result<(u64, Vec<structs::Issue>), mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("issues");
+
+    let skip: u64 = page_size * page_id;
+    let mut filter = doc! {};
+    if let Some(app_ids) = app_ids {
+        filter.insert("app_id", doc! { "$in": app_ids });
+    }
+    if let Some(status) = status {
+        filter.insert("status", status);
+    }
+    let mut cursor = collection
+        .find(
+            filter.clone(),
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "last_seen": -1 })
+                .skip(skip)
+                .limit(page_size as i64)
+                .build(),
+        )
+        .await?;
+
+    let mut result: Vec<structs::Issue> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let issue = structs::Issue {
+            _id: Some(_id.to_hex()),
+            fingerprint: doc.get_str("fingerprint").unwrap().to_string(),
+            app_id: doc.get_str("app_id").unwrap().to_string(),
+            type_: doc.get_str("type_").unwrap().to_string(),
+            message: doc.get_str("message").unwrap().to_string(),
+            last_message: doc.get_str("last_message").unwrap_or_default().to_string(),
+            first_seen: doc.get_i64("first_seen").unwrap(),
+            last_seen: doc.get_i64("last_seen").unwrap(),
+            count: doc.get_i64("count").unwrap(),
+            status: doc.get_str("status").unwrap().to_string(),
+        };
+        result.push(issue);
+    }
+
+    let count =
+        (collection.count_documents(filter, None).await?) as i64 - skip as i64 - page_size as i64;
+    let count = if count < 0 { 0 } else { count } as u64;
+
+    Ok((count, result))
+}
@@ -4,11 +4,12 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
-use crate::{structs, utils::check_auth_token::check_auth_token, AppState};
+use crate::{
+    auth::extractors::AuthUser, structs, utils::get_viewable_apps::get_viewable_apps, AppState,
+};
 
 #[derive(Deserialize)]
 pub struct GetLogsInput {
-    token: String,
     target_apps: Option<Vec<String>>,
     target_types: Option<Vec<String>>,
     page_id: u64,
@@ -18,20 +19,11 @@ pub struct GetLogsInput {
 
 pub async fn get_logs_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<GetLogsInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-    let app_ids = body.target_apps;
+    // Only the services the user has a role on
+    let app_ids = get_viewable_apps(app_state.clone(), &user, body.target_apps).await;
     let types = body.target_types;
     let page_id = body.page_id;
     let page_size = body.page_size;
@@ -107,12 +99,20 @@ async fn get_logs(
         let timestamp = doc.get("timestamp").unwrap().unwrap().as_i64().unwrap();
         let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
         let type_ = doc.get("type_").unwrap().unwrap().as_str().unwrap();
+        let fields = doc.get_document("fields").ok().map(|fields| {
+            mongodb::bson::Bson::Document(Document::try_from(fields).unwrap())
+                .into_relaxed_extjson()
+        });
         let log = structs::Log {
             _id: Some(_id.to_hex()),
             app_id: Some(app_id.to_string()),
             type_: Some(type_.to_string()),
             message: message.to_string(),
             timestamp: Some(timestamp),
+            fields,
+            trace_id: doc.get_str("trace_id").ok().map(|id| id.to_string()),
+            span_id: doc.get_str("span_id").ok().map(|id| id.to_string()),
+            parent_span_id: doc.get_str("parent_span_id").ok().map(|id| id.to_string()),
         };
         result.push(log);
     }
@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AuthUser, structs, utils::get_viewable_apps::get_viewable_apps, AppState,
+};
+
+// A trace is a single request, anything bigger is most likely a reused trace id
+const MAX_TRACE_LOGS: i64 = 10000;
+
+#[derive(Deserialize)]
+pub struct GetTraceInput {
+    trace_id: String,
+}
+
+pub async fn get_trace_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(body): Json<GetTraceInput>,
+) -> impl IntoResponse {
+    // Logs of the services the user has no role on are left out
+    let app_ids = get_viewable_apps(app_state.clone(), &user, None).await;
+
+    let logs = get_trace_logs(app_state, &body.trace_id, app_ids)
+        .await
+        .unwrap();
+    let spans = build_span_tree(&logs);
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "trace_id": body.trace_id,
+        "logs": logs,
+        "spans": spans,
+    });
+
+    Json(json_response)
+}
+
+// Logs of every service, oldest first
+async fn get_trace_logs(
+    app_state: Arc<AppState>,
+    trace_id: &str,
+    app_ids: Option<Vec<String>>,
+) -> Result<Vec<structs::Log>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("logs");
+
+    let mut filter = doc! {
+        "trace_id": trace_id,
+        "deleted": {
+            "$exists": false
+        }
+    };
+    if let Some(app_ids) = app_ids {
+        filter.insert("app_id", doc! { "$in": app_ids });
+    }
+    let mut cursor = collection
+        .find(
+            filter,
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "timestamp": 1, "_id": 1 })
+                .limit(MAX_TRACE_LOGS)
+                .build(),
+        )
+        .await?;
+
+    let mut result: Vec<structs::Log> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let message = doc.get("message").unwrap().unwrap().as_str().unwrap();
+        let app_id = doc.get("app_id").unwrap().unwrap().as_str().unwrap();
+        let timestamp = doc.get("timestamp").unwrap().unwrap().as_i64().unwrap();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let type_ = doc.get("type_").unwrap().unwrap().as_str().unwrap();
+        let fields = doc.get_document("fields").ok().map(|fields| {
+            mongodb::bson::Bson::Document(Document::try_from(fields).unwrap())
+                .into_relaxed_extjson()
+        });
+        let log = structs::Log {
+            _id: Some(_id.to_hex()),
+            app_id: Some(app_id.to_string()),
+            type_: Some(type_.to_string()),
+            message: message.to_string(),
+            timestamp: Some(timestamp),
+            fields,
+            trace_id: doc.get_str("trace_id").ok().map(|id| id.to_string()),
+            span_id: doc.get_str("span_id").ok().map(|id| id.to_string()),
+            parent_span_id: doc.get_str("parent_span_id").ok().map(|id| id.to_string()),
+        };
+        result.push(log);
+    }
+
+    Ok(result)
+}
+
+// Logs without a span_id are only part of the flat list
+fn build_span_tree(logs: &[structs::Log]) -> Vec<structs::TraceSpan> {
+    let mut spans: HashMap<String, structs::TraceSpan> = HashMap::new();
+    let mut order = Vec::new();
+    for log in logs {
+        let Some(span_id) = &log.span_id else {
+            continue;
+        };
+        let timestamp = log.timestamp.unwrap_or_default();
+        let span = spans.entry(span_id.clone()).or_insert_with(|| {
+            order.push(span_id.clone());
+            structs::TraceSpan {
+                span_id: span_id.clone(),
+                parent_span_id: None,
+                app_ids: Vec::new(),
+                start: timestamp,
+                end: timestamp,
+                logs: Vec::new(),
+                children: Vec::new(),
+            }
+        });
+        if span.parent_span_id.is_none() {
+            span.parent_span_id = log.parent_span_id.clone();
+        }
+        if let Some(app_id) = &log.app_id {
+            if !span.app_ids.contains(app_id) {
+                span.app_ids.push(app_id.clone());
+            }
+        }
+        span.start = span.start.min(timestamp);
+        span.end = span.end.max(timestamp);
+        span.logs.extend(log._id.clone());
+    }
+
+    // Spans are in the order of their first log, so are the children of each span
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    let mut roots = Vec::new();
+    for span_id in &order {
+        match &spans[span_id].parent_span_id {
+            Some(parent) if parent != span_id && spans.contains_key(parent) => {
+                children
+                    .entry(parent.clone())
+                    .or_default()
+                    .push(span_id.clone());
+            }
+            // The parent span may have logged nothing or be in a service that isn't monitored
+            _ => roots.push(span_id.clone()),
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut tree = Vec::new();
+    for span_id in roots {
+        tree.extend(take_span(&span_id, &mut spans, &children, &mut visited));
+    }
+    // Spans in a parent cycle are never reached from a root
+    for span_id in order {
+        tree.extend(take_span(&span_id, &mut spans, &children, &mut visited));
+    }
+    tree
+}
+
+fn take_span(
+    span_id: &str,
+    spans: &mut HashMap<String, structs::TraceSpan>,
+    children: &HashMap<String, Vec<String>>,
+    visited: &mut HashSet<String>,
+) -> Option<structs::TraceSpan> {
+    if !visited.insert(span_id.to_string()) {
+        return None;
+    }
+    let mut span = spans.remove(span_id)?;
+    for child in children.get(span_id).into_iter().flatten() {
+        span.children
+            .extend(take_span(child, spans, children, visited));
+    }
+    Some(span)
+}
@@ -1,2 +1,6 @@
 pub mod admin;
+pub mod get_ingestion_stats;
+pub mod get_issue_logs;
+pub mod get_issues;
 pub mod get_logs;
+pub mod get_trace;
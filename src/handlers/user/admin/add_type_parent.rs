@@ -4,62 +4,29 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
-use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
-    AppState,
-};
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
 
 #[derive(Deserialize)]
 pub struct AddTypeParentInput {
-    token: String,
     type_id: String,
     parent_id: String,
 }
 
 pub async fn add_type_parent_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<AddTypeParentInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     let type_id = mongodb::bson::oid::ObjectId::parse_str(&body.type_id).unwrap();
     let parent_id = body.parent_id;
 
     let db = app_state.db.clone();
 
     let collection: mongodb::Collection<Document> = db.collection("types");
+    let type_ = collection
+        .find_one(doc! { "_id": type_id }, None)
+        .await
+        .unwrap();
     let res = collection
         .update_one(
             doc! { "_id": type_id },
@@ -78,6 +45,22 @@ pub async fn add_type_parent_handler(
 
         return Json(json_response);
     }
+
+    let updated_type = collection
+        .find_one(doc! { "_id": type_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_type_parent",
+        "type",
+        Some(&body.type_id),
+        type_.as_ref(),
+        updated_type.as_ref(),
+    )
+    .await;
+
     return Json(serde_json::json!({
         "status": "success",
     }));
@@ -5,55 +5,22 @@ use mongodb::bson::doc;
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission, hash_password::hash_password,
-    },
+    auth::extractors::AdminUser,
+    utils::{audit_log::audit_log, hash_password::hash_password},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct AddUserInput {
-    token: String,
     username: String,
     password: String,
 }
 
 pub async fn add_user_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<AddUserInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     let username = body.username;
     let password = body.password;
     // hash password
@@ -75,9 +42,24 @@ pub async fn add_user_handler(
         return Json(json_response);
     }
     // insert into mongodb
-    let user = doc! { "username": username, "password": password_hash, "permissions": [] };
-    let res = db.collection("users").insert_one(user, None).await.unwrap();
+    let user =
+        doc! { "username": username, "password": password_hash, "permissions": [], "roles": [] };
+    let res = db
+        .collection("users")
+        .insert_one(user.clone(), None)
+        .await
+        .unwrap();
     let user_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_user",
+        "user",
+        Some(&user_id),
+        None,
+        Some(&user),
+    )
+    .await;
 
     return Json(serde_json::json!({
         "status": "success",
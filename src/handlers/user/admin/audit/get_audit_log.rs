@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Deserialize)]
+pub struct GetAuditLogInput {
+    // Every filter is optional, timestamps are in milliseconds
+    actor_id: Option<String>,
+    action: Option<String>,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    page_id: u64,
+    page_size: u64,
+}
+
+// Read only, audit entries can't be edited nor deleted through the API
+pub async fn get_audit_log_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+    Json(body): Json<GetAuditLogInput>,
+) -> impl IntoResponse {
+    let mut filter = doc! {};
+    if let Some(actor_id) = body.actor_id {
+        filter.insert("actor.user_id", actor_id);
+    }
+    if let Some(action) = body.action {
+        filter.insert("action", action);
+    }
+    if let Some(target_type) = body.target_type {
+        filter.insert("target_type", target_type);
+    }
+    if let Some(target_id) = body.target_id {
+        filter.insert("target_id", target_id);
+    }
+    let mut timestamp = doc! {};
+    if let Some(from) = body.from {
+        timestamp.insert("$gte", from);
+    }
+    if let Some(to) = body.to {
+        timestamp.insert("$lte", to);
+    }
+    if !timestamp.is_empty() {
+        filter.insert("timestamp", timestamp);
+    }
+
+    let page_size = body.page_size.clamp(1, MAX_PAGE_SIZE);
+    // The driver sends skip as an i64
+    let skip = body.page_id.saturating_mul(page_size).min(i64::MAX as u64);
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("audit_log");
+    let mut cursor = collection
+        .find(
+            filter.clone(),
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "timestamp": -1 })
+                .skip(skip)
+                .limit(page_size as i64)
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let mut entries: Vec<structs::AuditEntry> = Vec::new();
+    while cursor.advance().await.unwrap() {
+        let doc = cursor.deserialize_current().unwrap();
+        let actor = doc.get_document("actor").unwrap();
+        let entry = structs::AuditEntry {
+            _id: doc.get_object_id("_id").unwrap().to_hex(),
+            timestamp: doc.get_i64("timestamp").unwrap(),
+            actor: structs::AuditActor {
+                user_id: actor.get_str("user_id").unwrap().to_string(),
+                username: actor.get_str("username").ok().map(String::from),
+                api_key_id: actor.get_str("api_key_id").ok().map(String::from),
+            },
+            action: doc.get_str("action").unwrap().to_string(),
+            target_type: doc.get_str("target_type").unwrap().to_string(),
+            target_id: doc.get_str("target_id").ok().map(String::from),
+            changes: mongodb::bson::Bson::Document(
+                doc.get_document("changes").cloned().unwrap_or_default(),
+            )
+            .into_relaxed_extjson(),
+            ip: doc.get_str("ip").ok().map(String::from),
+            forwarded_for: doc.get_str("forwarded_for").ok().map(String::from),
+        };
+        entries.push(entry);
+    }
+
+    let total = collection.count_documents(filter, None).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "entries": entries,
+        "total": total,
+    }))
+}
@@ -4,66 +4,44 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::doc;
 use serde::Deserialize;
 
-use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
-    AppState,
-};
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
 
 #[derive(Deserialize)]
 pub struct CreateServiceInput {
-    token: String,
     app_name: String,
+    // Roles can be granted on every service of a group
+    group: Option<String>,
 }
 
 pub async fn create_service_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<CreateServiceInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     let app_name = body.app_name;
 
     // insert into mongodb
-    let app = doc! { "app_name": app_name };
+    let mut app = doc! { "app_name": app_name };
+    if let Some(group) = body.group.filter(|group| !group.is_empty()) {
+        app.insert("group", group);
+    }
     let db = &app_state.db;
     let res = db
         .collection("services")
-        .insert_one(app, None)
+        .insert_one(app.clone(), None)
         .await
         .unwrap();
     let service_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "create_service",
+        "service",
+        Some(&service_id),
+        None,
+        Some(&app),
+    )
+    .await;
     return Json(serde_json::json!({
         "status": "success",
         "_id": service_id,
@@ -5,49 +5,37 @@ use mongodb::bson::doc;
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission, user::db::update_db_datas::update_db_datas,
-    },
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role, user::db::update_db_datas::update_db_datas},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct AddDbInput {
-    token: String,
     name: String,
     connection_string: String,
+    // Links the database to a service, its backups then follow the roles of the service
+    app_id: Option<String>,
 }
 
 pub async fn add_db_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<AddDbInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
+    let has_perm = has_role(
         app_state.clone(),
+        &user,
+        Role::Admin,
+        body.app_id.as_deref(),
     )
     .await;
 
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the admin role on this service",
             "error_code": "permission_denied"
         });
 
@@ -58,11 +46,24 @@ pub async fn add_db_handler(
     let connection_string = body.connection_string;
 
     // insert into mongodb
-    let app = doc! { "name": db_name.clone(), "custom_name": db_name.clone(), "connection_string": connection_string.clone(), "status": "connecting", "collections": []};
+    let mut app = doc! { "name": db_name.clone(), "custom_name": db_name.clone(), "connection_string": connection_string.clone(), "status": "connecting", "collections": []};
+    if let Some(app_id) = body.app_id {
+        app.insert("app_id", app_id);
+    }
     let db = &app_state.db;
     let collection = db.collection("databases");
-    let res = collection.insert_one(app, None).await.unwrap();
+    let res = collection.insert_one(app.clone(), None).await.unwrap();
     let db_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &user,
+        "add_db",
+        "database",
+        Some(&db_id),
+        None,
+        Some(&app),
+    )
+    .await;
 
     // Do not wait for the update to finish before returning
     tokio::spawn(update_db_datas(
@@ -8,48 +8,34 @@ use mongodb::{
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role, user::db::get_db_app_id::get_db_app_id},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct DeleteDbInput {
-    token: String,
     db_id: String,
 }
 
 pub async fn delete_db_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<DeleteDbInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
+    let app_id = get_db_app_id(
+        &app_state.db,
+        mongodb::bson::oid::ObjectId::parse_str(&body.db_id).unwrap(),
     )
     .await;
 
+    let has_perm = has_role(app_state.clone(), &user, Role::Admin, app_id.as_deref()).await;
+
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the admin role on this database",
             "error_code": "permission_denied"
         });
 
@@ -60,10 +46,20 @@ pub async fn delete_db_handler(
 
     let db = &app_state.db;
     let collection: Collection<Document> = db.collection("databases");
-    collection
-        .delete_one(doc! {"_id": db_id}, None)
+    let database = collection
+        .find_one_and_delete(doc! {"_id": db_id}, None)
         .await
         .unwrap();
+    audit_log(
+        &app_state,
+        &user,
+        "delete_db",
+        "database",
+        Some(&body.db_id),
+        database.as_ref(),
+        None,
+    )
+    .await;
     return Json(serde_json::json!({
         "status": "success",
     }));
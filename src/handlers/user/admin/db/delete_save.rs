@@ -1,61 +1,68 @@
 use std::sync::Arc;
 
 use axum::{extract::State, response::IntoResponse, Json};
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
 use crate::{
+    auth::extractors::AuthUser,
+    structs::Role,
     utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission, user::db::delete_save,
+        audit_log::audit_log,
+        has_role::has_role,
+        user::db::{delete_save, get_db_app_id::get_db_app_id},
     },
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct DeleteSaveInput {
-    token: String,
     save_id: String,
 }
 
 pub async fn delete_save_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<DeleteSaveInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
+    let save_id = mongodb::bson::oid::ObjectId::parse_str(&body.save_id).unwrap();
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("db_saves");
+    let save = collection
+        .find_one(doc! { "_id": save_id }, None)
+        .await
+        .unwrap();
+    let app_id = match save
+        .as_ref()
+        .and_then(|save| save.get_object_id("db_id").ok())
+    {
+        Some(db_id) => get_db_app_id(db, db_id).await,
+        None => None,
+    };
 
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
+    let has_perm = has_role(app_state.clone(), &user, Role::Admin, app_id.as_deref()).await;
 
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the admin role on this database",
             "error_code": "permission_denied"
         });
 
         return Json(json_response);
     }
 
-    let save_id = mongodb::bson::oid::ObjectId::parse_str(&body.save_id).unwrap();
-
     delete_save::delete_save(&app_state.db.clone(), save_id).await;
+    audit_log(
+        &app_state,
+        &user,
+        "delete_save",
+        "db_save",
+        Some(&body.save_id),
+        save.as_ref(),
+        None,
+    )
+    .await;
 
     return Json(serde_json::json!({
         "status": "success",
@@ -1,10 +1,9 @@
 use std::sync::Arc;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role, user::db::get_db_app_id::get_db_app_id},
     AppState,
 };
 use axum::{
@@ -34,35 +33,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DownloadSaveQuery {
     save_id: String,
-    token: String,
 }
 
 pub async fn download_save_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Query(query): Query<DownloadSaveQuery>,
 ) -> impl IntoResponse {
     let save_id = query.save_id;
-    let token = query.token;
-    let valid = check_auth_token(app_state.clone(), token.clone().to_string());
-    if !valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid token or token expired"));
-    }
-
-    let token_data = get_token_data(app_state.clone(), token.to_string());
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        return Err((
-            StatusCode::FORBIDDEN,
-            "You don't have administrator permission",
-        ));
-    }
 
     let db = app_state.db.clone();
     let collection: Collection<Document> = db.collection("db_saves");
@@ -79,6 +57,28 @@ pub async fn download_save_handler(
     let time = document.get("time").unwrap().as_i64().unwrap();
     let db_id = document.get("db_id").unwrap().as_object_id().unwrap();
 
+    let app_id = get_db_app_id(&db, db_id).await;
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, app_id.as_deref()).await;
+
+    if !has_perm {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "You don't have the operator role on this database",
+        ));
+    }
+
+    // Backups hold the whole database, every download is recorded
+    audit_log(
+        &app_state,
+        &user,
+        "download_save",
+        "db_save",
+        Some(&save_id),
+        None,
+        None,
+    )
+    .await;
+
     let path = format!("db_saves/{}/{}", db_id.clone(), time.clone());
 
     // Create zip
@@ -8,78 +8,108 @@ use mongodb::{
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::Role,
+    utils::{audit_log::audit_log, has_role::has_role, user::db::get_db_app_id::get_db_app_id},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct EditDbInput {
-    token: String,
     db_id: String,
     name: String,
     custom_name: String,
     connection_string: String,
     authentication_database: String,
+    // Links the database to another service, an empty app_id unlinks it
+    app_id: Option<String>,
 }
 
 pub async fn edit_db_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<EditDbInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
+    let app_id = get_db_app_id(
+        &app_state.db,
+        mongodb::bson::oid::ObjectId::parse_str(&body.db_id).unwrap(),
     )
     .await;
 
+    let has_perm = has_role(app_state.clone(), &user, Role::Admin, app_id.as_deref()).await;
+
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the admin role on this database",
             "error_code": "permission_denied"
         });
 
         return Json(json_response);
     }
 
+    // Moving the database needs the admin role on its new scope too
+    if let Some(new_app_id) = &body.app_id {
+        let new_app_id = Some(new_app_id.as_str()).filter(|app_id| !app_id.is_empty());
+        if !has_role(app_state.clone(), &user, Role::Admin, new_app_id).await {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "You don't have the admin role on the new service",
+                "error_code": "permission_denied"
+            });
+
+            return Json(json_response);
+        }
+    }
+
     let db_id = mongodb::bson::oid::ObjectId::parse_str(&body.db_id).unwrap();
     let db_name = body.name;
     let connection_string = body.connection_string;
     let custom_name = body.custom_name;
 
+    let mut update = doc! {"$set": {
+        "name": db_name.clone(),
+        "connection_string": connection_string.clone(),
+        "custom_name": custom_name.clone(),
+        "authentication_database": body.authentication_database.clone(),
+    }};
+    match body.app_id.as_deref() {
+        Some("") => {
+            update.insert("$unset", doc! {"app_id": ""});
+        }
+        Some(app_id) => {
+            update
+                .get_document_mut("$set")
+                .unwrap()
+                .insert("app_id", app_id);
+        }
+        None => {}
+    }
+
     let db = &app_state.db;
     let collection: Collection<Document> = db.collection("databases");
+    let database = collection
+        .find_one(doc! {"_id": db_id}, None)
+        .await
+        .unwrap();
     collection
-        .update_one(
-            doc! {"_id": db_id.clone()},
-            doc! {"$set": {
-                "name": db_name.clone(),
-                "connection_string": connection_string.clone(),
-                "custom_name": custom_name.clone(),
-                "authentication_database": body.authentication_database.clone(),
-            }},
-            None,
-        )
+        .update_one(doc! {"_id": db_id.clone()}, update, None)
+        .await
+        .unwrap();
+    let updated_database = collection
+        .find_one(doc! {"_id": db_id}, None)
         .await
         .unwrap();
+    audit_log(
+        &app_state,
+        &user,
+        "edit_db",
+        "database",
+        Some(&body.db_id),
+        database.as_ref(),
+        updated_database.as_ref(),
+    )
+    .await;
     return Json(serde_json::json!({
         "status": "success",
     }));
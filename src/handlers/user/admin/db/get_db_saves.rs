@@ -4,50 +4,35 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, oid::ObjectId, Document};
 
 use crate::{
-    structs,
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::{self, Role},
+    utils::{has_role::has_role, user::db::get_db_app_id::get_db_app_id},
     AppState,
 };
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct GetDbSavesInput {
-    token: String,
     db_id: String,
 }
 
 pub async fn get_db_saves_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<GetDbSavesInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
+    let app_id = get_db_app_id(
+        &app_state.db,
+        mongodb::bson::oid::ObjectId::parse_str(&body.db_id).unwrap(),
     )
     .await;
 
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, app_id.as_deref()).await;
+
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the operator role on this database",
             "error_code": "permission_denied"
         });
 
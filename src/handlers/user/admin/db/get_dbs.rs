@@ -7,51 +7,22 @@ use mongodb::{
 };
 
 use crate::{
-    structs::{self},
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AuthUser,
+    structs::{self, Role},
+    utils::get_accessible_services::get_accessible_services,
     AppState,
 };
 
 pub async fn get_dbs_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<structs::AuthTokenJSON>,
+    user: AuthUser,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
+    // Connection strings hold credentials, so listing them needs the operator role.
+    // Databases not linked to a service are only listed with a global role
+    let app_ids = get_accessible_services(app_state.clone(), &user, Role::Operator).await;
 
     // get from mongodb
-    let dbs: Vec<structs::Database> = get_dbs(app_state).await.unwrap();
+    let dbs: Vec<structs::Database> = get_dbs(app_state, app_ids).await.unwrap();
 
     return Json(serde_json::json!({
         "status": "success",
@@ -61,11 +32,16 @@ pub async fn get_dbs_handler(
 
 async fn get_dbs(
     app_state: Arc<AppState>,
+    app_ids: Option<Vec<String>>,
 ) -> Result<Vec<structs::Database>, mongodb::error::Error> {
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("databases");
 
-    let mut cursor = collection.find(doc! {}, None).await?;
+    let filter = match app_ids {
+        Some(app_ids) => doc! { "app_id": { "$in": app_ids } },
+        None => doc! {},
+    };
+    let mut cursor = collection.find(filter, None).await?;
 
     let mut result: Vec<structs::Database> = Vec::new();
     while cursor.advance().await? {
@@ -136,6 +112,7 @@ async fn get_dbs(
             message: message.to_string(),
             custom_name: custom_name.to_string(),
             authentication_database: authentication_database.to_string(),
+            app_id: doc.get_str("app_id").ok().map(|app_id| app_id.to_string()),
         };
         result.push(database);
     }
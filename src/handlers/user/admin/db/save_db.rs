@@ -8,48 +8,38 @@ use mongodb::{
 use serde::Deserialize;
 
 use crate::{
+    auth::extractors::AuthUser,
+    structs::Role,
     utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission, user::db::secure_save_db::secure_save_db,
+        audit_log::audit_log,
+        has_role::has_role,
+        user::db::{get_db_app_id::get_db_app_id, secure_save_db::secure_save_db},
     },
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct SaveDbInput {
-    token: String,
     db_id: String,
 }
 
 pub async fn save_db_handler(
     State(app_state): State<Arc<AppState>>,
+    user: AuthUser,
     Json(body): Json<SaveDbInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
+    let app_id = get_db_app_id(
+        &app_state.db,
+        mongodb::bson::oid::ObjectId::parse_str(&body.db_id).unwrap(),
     )
     .await;
 
+    let has_perm = has_role(app_state.clone(), &user, Role::Operator, app_id.as_deref()).await;
+
     if !has_perm {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "You don't have administrator permission",
+            "message": "You don't have the operator role on this database",
             "error_code": "permission_denied"
         });
 
@@ -85,6 +75,17 @@ pub async fn save_db_handler(
     )
     .await;
 
+    audit_log(
+        &app_state,
+        &user,
+        "save_db",
+        "database",
+        Some(&body.db_id),
+        None,
+        Some(&doc! { "success": res.success, "message": &res.message }),
+    )
+    .await;
+
     return Json(serde_json::json!({
         "status": if res.success {"success"} else {"error"},
         "success": res.success,
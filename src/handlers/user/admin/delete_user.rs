@@ -5,49 +5,27 @@ use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
 use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
+    auth::extractors::AdminUser,
+    utils::{audit_log::audit_log, user::session::revoke_sessions::revoke_sessions},
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct DeleteUserInput {
-    token: String,
     user_id: String,
 }
 
 pub async fn delete_user_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<DeleteUserInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
+    // Deleting yourself could leave the instance without any administrator
+    if body.user_id == admin.user_id {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
+            "message": "You can't delete your own account",
+            "error_code": "cannot_delete_self"
         });
 
         return Json(json_response);
@@ -59,7 +37,23 @@ pub async fn delete_user_handler(
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("users");
     let filter = doc! { "_id": user_id };
-    collection.delete_one(filter, None).await.unwrap();
+    let user = collection.find_one_and_delete(filter, None).await.unwrap();
+    revoke_sessions(db, &body.user_id, None).await;
+    let api_keys: mongodb::Collection<Document> = db.collection("api_keys");
+    api_keys
+        .delete_many(doc! { "user_id": &body.user_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "delete_user",
+        "user",
+        Some(&body.user_id),
+        user.as_ref(),
+        None,
+    )
+    .await;
 
     return Json(serde_json::json!({
         "status": "success",
@@ -4,70 +4,55 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
-use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
-    AppState,
-};
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
 
 #[derive(Deserialize)]
 pub struct EditSericeInput {
-    token: String,
     app_id: String,
     new_app_name: String,
+    // An empty group removes the service from its group
+    new_group: Option<String>,
 }
 
 pub async fn edit_service_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<EditSericeInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     let app_id = mongodb::bson::oid::ObjectId::parse_str(&body.app_id).unwrap();
     let new_app_name = body.new_app_name;
 
+    let update = match body.new_group.as_deref() {
+        None => doc! { "$set": { "app_name": new_app_name } },
+        Some("") => doc! { "$set": { "app_name": new_app_name }, "$unset": { "group": "" } },
+        Some(group) => doc! { "$set": { "app_name": new_app_name, "group": group } },
+    };
+
     // update mongodb
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("services");
+    let service = collection
+        .find_one(doc! { "_id": app_id }, None)
+        .await
+        .unwrap();
     collection
-        .update_one(
-            doc! { "_id": app_id },
-            doc! { "$set": { "app_name": new_app_name } },
-            None,
-        )
+        .update_one(doc! { "_id": app_id }, update, None)
         .await
         .unwrap();
+    let updated_service = collection
+        .find_one(doc! { "_id": app_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "edit_service",
+        "service",
+        Some(&body.app_id),
+        service.as_ref(),
+        updated_service.as_ref(),
+    )
+    .await;
 
     return Json(serde_json::json!({
         "status": "success",
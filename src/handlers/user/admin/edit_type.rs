@@ -4,70 +4,39 @@ use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
-use crate::{
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
-    AppState,
-};
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
 
 #[derive(Deserialize)]
 pub struct EditTypeInput {
-    token: String,
     type_id: String,
     name: String,
     color: String,
     icon: String,
     notifications: Vec<String>,
     importance: i32,
+    notify_new_issues_only: Option<bool>,
 }
 
 pub async fn edit_type_handler(
     State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
     Json(body): Json<EditTypeInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     let _id = mongodb::bson::oid::ObjectId::parse_str(&body.type_id).unwrap();
     let name = body.name;
     let color = body.color;
     let icon = body.icon;
     let notifications = body.notifications;
     let importance = body.importance;
+    let notify_new_issues_only = body.notify_new_issues_only.unwrap_or(false);
 
     // update mongodb
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("types");
+    let type_ = collection
+        .find_one(doc! { "_id": _id }, None)
+        .await
+        .unwrap();
     collection
         .update_one(
             doc! { "_id": _id },
@@ -78,12 +47,27 @@ pub async fn edit_type_handler(
                     "icon": icon,
                     "notifications": notifications,
                     "importance": importance,
+                    "notify_new_issues_only": notify_new_issues_only,
                 }
             },
             None,
         )
         .await
         .unwrap();
+    let updated_type = collection
+        .find_one(doc! { "_id": _id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "edit_type",
+        "type",
+        Some(&body.type_id),
+        type_.as_ref(),
+        updated_type.as_ref(),
+    )
+    .await;
 
     return Json(serde_json::json!({
         "status": "success",
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    auth::extractors::AdminUser,
+    structs,
+    utils::logs_service_side::{
+        ingestion_stats::get_day,
+        rate_limit::{get_service_limits, refill_bucket},
+    },
+    AppState,
+};
+
+pub async fn get_services_usage_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    let usage: Vec<structs::ServiceUsage> = get_services_usage(app_state).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "usage": usage,
+    }))
+}
+
+async fn get_services_usage(
+    app_state: Arc<AppState>,
+) -> Result<Vec<structs::ServiceUsage>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let services: mongodb::Collection<Document> = db.collection("services");
+    let stats: mongodb::Collection<Document> = db.collection("ingestion_stats");
+    let day = get_day(chrono::Utc::now().timestamp_millis());
+
+    let mut cursor = services.find(None, None).await?;
+    let mut result: Vec<structs::ServiceUsage> = Vec::new();
+    while let Some(service) = cursor.try_next().await? {
+        let app_id = service.get_object_id("_id").unwrap().to_hex();
+        let limits = get_service_limits(&app_state, &service);
+        let today = stats
+            .find_one(doc! { "app_id": app_id.clone(), "day": day.clone() }, None)
+            .await?
+            .unwrap_or_default();
+        let available_tokens = if limits.rate_limit_per_second > 0.0 {
+            Some(refill_bucket(&app_state, &app_id, &limits))
+        } else {
+            None
+        };
+
+        result.push(structs::ServiceUsage {
+            app_id,
+            app_name: service.get_str("app_name").unwrap_or_default().to_string(),
+            rate_limit_per_second: limits.rate_limit_per_second,
+            rate_limit_burst: limits.rate_limit_burst,
+            daily_quota: limits.daily_quota,
+            available_tokens,
+            day: day.clone(),
+            received: today.get_i64("received").unwrap_or(0),
+            rejected: today.get_i64("rejected").unwrap_or(0),
+            sampled: today.get_i64("sampled").unwrap_or(0),
+            dropped: today.get_i64("dropped").unwrap_or(0),
+        });
+    }
+
+    Ok(result)
+}
@@ -3,50 +3,12 @@ use std::sync::Arc;
 use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 
-use crate::{
-    structs,
-    utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
-        has_permission::has_permission,
-    },
-    AppState,
-};
+use crate::{auth::extractors::AdminUser, structs, AppState};
 
 pub async fn get_users_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<structs::AuthTokenJSON>,
+    _: AdminUser,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token);
-
-    let has_perm = has_permission(
-        token_data.user_id,
-        "administrator".to_string(),
-        app_state.clone(),
-    )
-    .await;
-
-    if !has_perm {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "You don't have administrator permission",
-            "error_code": "permission_denied"
-        });
-
-        return Json(json_response);
-    }
-
     // get from mongodb
     let users: Vec<structs::User> = get_users(app_state).await.unwrap();
 
@@ -76,6 +38,17 @@ async fn get_users(app_state: Arc<AppState>) -> Result<Vec<structs::User>, mongo
             _id: Some(_id.to_hex()),
             username: username.to_string(),
             permissions: permissions_result,
+            roles: doc
+                .get_array("roles")
+                .map(|roles| {
+                    roles
+                        .into_iter()
+                        .filter_map(|role| {
+                            mongodb::bson::from_slice(role.unwrap().as_document()?.as_bytes()).ok()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
             password: None,
         };
         result.push(user);
@@ -1,6 +1,7 @@
 pub mod add_type;
 pub mod add_type_parent;
 pub mod add_user;
+pub mod audit;
 pub mod create_service;
 pub mod db;
 pub mod delete_service;
@@ -8,8 +9,19 @@ pub mod delete_type;
 pub mod delete_user;
 pub mod edit_service;
 pub mod edit_type;
+pub mod get_services_usage;
 pub mod get_users;
+pub mod pipeline;
+pub mod redaction;
 pub mod remove_type_parent;
+pub mod sampling;
+pub mod session;
 pub mod set_discord_webhook;
+pub mod set_require_admin_2fa;
+pub mod set_service_limits;
 pub mod set_telegram_chat;
-pub mod set_user_permissions;
+pub mod set_user_roles;
+pub mod sinks;
+pub mod syslog;
+pub mod unlock_user;
+pub mod webhook;
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct DeletePipelineInput {
+    app_id: String,
+}
+
+pub async fn delete_pipeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<DeletePipelineInput>,
+) -> impl IntoResponse {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("pipelines");
+    let pipeline = collection
+        .find_one_and_delete(doc! { "app_id": &body.app_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "delete_pipeline",
+        "pipeline",
+        Some(&body.app_id),
+        pipeline.as_ref(),
+        None,
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
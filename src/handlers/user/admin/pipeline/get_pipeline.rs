@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser, utils::logs_service_side::pipeline::get_pipeline, AppState,
+};
+
+#[derive(Deserialize)]
+pub struct GetPipelineInput {
+    app_id: String,
+}
+
+pub async fn get_pipeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+    Json(body): Json<GetPipelineInput>,
+) -> impl IntoResponse {
+    let steps = get_pipeline(&app_state.db, &body.app_id)
+        .await
+        .unwrap_or_default();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "steps": steps,
+    }))
+}
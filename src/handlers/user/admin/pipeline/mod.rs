@@ -0,0 +1,4 @@
+pub mod delete_pipeline;
+pub mod get_pipeline;
+pub mod set_pipeline;
+pub mod test_pipeline;
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::{
+    bson::{doc, Document},
+    options::UpdateOptions,
+};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::{
+        audit_log::audit_log,
+        logs_service_side::pipeline::{validate_pipeline, PipelineStep},
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct SetPipelineInput {
+    app_id: String,
+    steps: Vec<PipelineStep>,
+}
+
+pub async fn set_pipeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<SetPipelineInput>,
+) -> impl IntoResponse {
+    let valid_pipeline = validate_pipeline(&body.steps);
+    if valid_pipeline.is_err() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": valid_pipeline.err().unwrap(),
+            "error_code": "invalid_pipeline"
+        });
+
+        return Json(json_response);
+    }
+
+    let steps = mongodb::bson::to_bson(&body.steps).unwrap();
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("pipelines");
+    let pipeline = collection
+        .find_one(doc! { "app_id": &body.app_id }, None)
+        .await
+        .unwrap();
+    collection
+        .update_one(
+            doc! { "app_id": &body.app_id },
+            doc! { "$set": { "steps": steps } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .unwrap();
+    let updated_pipeline = collection
+        .find_one(doc! { "app_id": &body.app_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "set_pipeline",
+        "pipeline",
+        Some(&body.app_id),
+        pipeline.as_ref(),
+        updated_pipeline.as_ref(),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
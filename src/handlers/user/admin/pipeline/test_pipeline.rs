@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::logs_service_side::pipeline::{
+        compile_pipeline, get_pipeline, run_pipeline, validate_pipeline, PipelineStep,
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct TestPipelineInput {
+    // Test the saved pipeline of a service, or the given steps
+    app_id: Option<String>,
+    steps: Option<Vec<PipelineStep>>,
+    r#type: Option<String>,
+    message: String,
+    timestamp: Option<i64>,
+}
+
+pub async fn test_pipeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+    Json(body): Json<TestPipelineInput>,
+) -> impl IntoResponse {
+    let steps = match (body.steps, body.app_id) {
+        (Some(steps), _) => steps,
+        (None, Some(app_id)) => get_pipeline(&app_state.db, &app_id)
+            .await
+            .unwrap_or_default(),
+        (None, None) => vec![],
+    };
+
+    let valid_pipeline = validate_pipeline(&steps);
+    if valid_pipeline.is_err() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": valid_pipeline.err().unwrap(),
+            "error_code": "invalid_pipeline"
+        });
+
+        return Json(json_response);
+    }
+
+    let output = run_pipeline(
+        &compile_pipeline(&steps),
+        body.r#type,
+        body.message,
+        body.timestamp,
+        serde_json::Map::new(),
+    );
+
+    Json(serde_json::json!({
+        "status": "success",
+        "result": output,
+    }))
+}
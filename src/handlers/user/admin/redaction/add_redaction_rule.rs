@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::{audit_log::audit_log, logs_service_side::redact::compile_rule},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct AddRedactionRuleInput {
+    name: String,
+    app_id: Option<String>,
+    preset: Option<String>,
+    pattern: Option<String>,
+    replacement: Option<String>,
+}
+
+pub async fn add_redaction_rule_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<AddRedactionRuleInput>,
+) -> impl IntoResponse {
+    if body.preset.is_some() == body.pattern.is_some() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Specify either a preset or a pattern",
+            "error_code": "invalid_rule"
+        });
+
+        return Json(json_response);
+    }
+
+    let rule = doc! {
+        "name": body.name,
+        "app_id": body.app_id,
+        "preset": body.preset,
+        "pattern": body.pattern,
+        "replacement": body.replacement,
+    };
+    // Null fields are not stored so that defaults apply
+    let rule: Document = rule
+        .into_iter()
+        .filter(|(_, value)| value.as_null().is_none())
+        .collect();
+
+    let compiled = compile_rule(&rule);
+    if compiled.is_err() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": compiled.err().unwrap(),
+            "error_code": "invalid_rule"
+        });
+
+        return Json(json_response);
+    }
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("redaction_rules");
+    let res = collection.insert_one(rule.clone(), None).await.unwrap();
+    let rule_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_redaction_rule",
+        "redaction_rule",
+        Some(&rule_id),
+        None,
+        Some(&rule),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "_id": rule_id,
+    }))
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+pub async fn get_redaction_rules_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    // get from mongodb
+    let rules: Vec<structs::RedactionRule> = get_redaction_rules(app_state).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "rules": rules,
+    }))
+}
+
+async fn get_redaction_rules(
+    app_state: Arc<AppState>,
+) -> Result<Vec<structs::RedactionRule>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("redaction_rules");
+
+    let mut cursor = collection.find(doc! {}, None).await?;
+
+    let mut result: Vec<structs::RedactionRule> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let rule = structs::RedactionRule {
+            _id: Some(_id.to_hex()),
+            name: doc.get_str("name").unwrap_or_default().to_string(),
+            app_id: doc.get_str("app_id").ok().map(|app_id| app_id.to_string()),
+            preset: doc.get_str("preset").ok().map(|preset| preset.to_string()),
+            pattern: doc
+                .get_str("pattern")
+                .ok()
+                .map(|pattern| pattern.to_string()),
+            replacement: doc
+                .get_str("replacement")
+                .ok()
+                .map(|replacement| replacement.to_string()),
+        };
+        result.push(rule);
+    }
+
+    Ok(result)
+}
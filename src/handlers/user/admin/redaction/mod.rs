@@ -0,0 +1,4 @@
+pub mod add_redaction_rule;
+pub mod delete_redaction_rule;
+pub mod get_redaction_rules;
+pub mod test_redaction;
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::logs_service_side::redact::{get_redaction_rules, redact},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct TestRedactionInput {
+    app_id: Option<String>,
+    message: String,
+}
+
+pub async fn test_redaction_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+    Json(body): Json<TestRedactionInput>,
+) -> impl IntoResponse {
+    let rules = get_redaction_rules(&app_state, body.app_id.as_deref()).await;
+    let (redacted, matched_rules) = redact(&body.message, &rules);
+
+    Json(serde_json::json!({
+        "status": "success",
+        "redacted": redacted,
+        "matched_rules": matched_rules,
+    }))
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct AddSamplingRuleInput {
+    // Rules without app_id or type apply to every service or type
+    app_id: Option<String>,
+    r#type: Option<String>,
+    // Fraction of the logs to keep, between 0 and 1
+    rate: Option<f64>,
+    drop: Option<bool>,
+}
+
+pub async fn add_sampling_rule_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<AddSamplingRuleInput>,
+) -> impl IntoResponse {
+    let drop = body.drop.unwrap_or(false);
+    let rate = body.rate.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&rate) {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "The rate must be between 0 and 1",
+            "error_code": "invalid_rule"
+        });
+
+        return Json(json_response);
+    }
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("sampling_rules");
+    let rule = doc! {
+        "app_id": body.app_id,
+        "type_": body.r#type,
+        "rate": rate,
+        "drop": drop,
+    };
+    let res = collection.insert_one(rule.clone(), None).await.unwrap();
+    let rule_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_sampling_rule",
+        "sampling_rule",
+        Some(&rule_id),
+        None,
+        Some(&rule),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "_id": rule_id,
+    }))
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+pub async fn get_sampling_rules_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    // get from mongodb
+    let rules: Vec<structs::SamplingRule> = get_sampling_rules(app_state).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "rules": rules,
+    }))
+}
+
+async fn get_sampling_rules(
+    app_state: Arc<AppState>,
+) -> Result<Vec<structs::SamplingRule>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("sampling_rules");
+
+    let mut cursor = collection.find(doc! {}, None).await?;
+
+    let mut result: Vec<structs::SamplingRule> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let rule = structs::SamplingRule {
+            _id: Some(_id.to_hex()),
+            app_id: doc.get_str("app_id").ok().map(|app_id| app_id.to_string()),
+            type_: doc.get_str("type_").ok().map(|type_| type_.to_string()),
+            rate: doc.get_f64("rate").unwrap_or(1.0),
+            drop: doc.get_bool("drop").unwrap_or(false),
+        };
+        result.push(rule);
+    }
+
+    Ok(result)
+}
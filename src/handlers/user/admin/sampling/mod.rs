@@ -0,0 +1,3 @@
+pub mod add_sampling_rule;
+pub mod delete_sampling_rule;
+pub mod get_sampling_rules;
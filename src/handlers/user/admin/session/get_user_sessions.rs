@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser, structs, utils::user::session::get_sessions::get_sessions,
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct GetUserSessionsInput {
+    user_id: String,
+}
+
+pub async fn get_user_sessions_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+    Json(body): Json<GetUserSessionsInput>,
+) -> impl IntoResponse {
+    let sessions: Vec<structs::Session> = get_sessions(&app_state.db, &body.user_id, None)
+        .await
+        .unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "sessions": sessions,
+    }))
+}
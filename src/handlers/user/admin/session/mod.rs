@@ -0,0 +1,2 @@
+pub mod get_user_sessions;
+pub mod revoke_user_sessions;
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::{audit_log::audit_log, user::session::revoke_sessions::revoke_sessions},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RevokeUserSessionsInput {
+    user_id: String,
+    // Every session of the user is revoked when not specified
+    session_id: Option<String>,
+}
+
+pub async fn revoke_user_sessions_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<RevokeUserSessionsInput>,
+) -> impl IntoResponse {
+    let revoked = match &body.session_id {
+        Some(session_id) => {
+            let collection: mongodb::Collection<Document> = app_state.db.collection("sessions");
+            collection
+                .delete_one(doc! { "_id": session_id, "user_id": &body.user_id }, None)
+                .await
+                .unwrap()
+                .deleted_count
+        }
+        None => revoke_sessions(&app_state.db, &body.user_id, None).await,
+    };
+
+    audit_log(
+        &app_state,
+        &admin,
+        "revoke_user_sessions",
+        "user",
+        Some(&body.user_id),
+        None,
+        Some(&doc! { "revoked_session": &body.session_id, "revoked": revoked as i64 }),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "revoked": revoked,
+    }))
+}
@@ -0,0 +1,56 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::doc;
+use serde::Deserialize;
+use std::{fs::File, sync::Arc};
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::{audit_log::audit_log, user::totp::has_second_factor::has_second_factor},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct SetRequireAdmin2faInput {
+    require: bool,
+}
+
+// Administrators without TOTP keep access to their account but not to admin routes
+pub async fn set_require_admin_2fa_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<SetRequireAdmin2faInput>,
+) -> impl IntoResponse {
+    // Otherwise the caller would lose access to the admin routes right away
+    if body.require && !has_second_factor(&app_state.db, &admin.user_id).await {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Enable two-factor authentication on your account first",
+            "error_code": "totp_not_enabled"
+        });
+
+        return Json(json_response);
+    }
+
+    // Write in config.json
+    let config_file = File::open("config.json").unwrap();
+    let mut config: serde_json::Value = serde_json::from_reader(config_file).unwrap();
+    let before = doc! { "require_admin_2fa": config["require_admin_2fa"].as_bool() };
+    config["require_admin_2fa"] = serde_json::json!(body.require);
+    let config_file = File::create("config.json").unwrap();
+    serde_json::to_writer_pretty(config_file, &config).unwrap();
+
+    audit_log(
+        &app_state,
+        &admin,
+        "set_require_admin_2fa",
+        "config",
+        Some("require_admin_2fa"),
+        Some(&before),
+        Some(&doc! { "require_admin_2fa": body.require }),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct SetServiceLimitsInput {
+    app_id: String,
+    // Missing values fall back to the defaults of the config
+    rate_limit_per_second: Option<f64>,
+    rate_limit_burst: Option<f64>,
+    daily_quota: Option<i64>,
+}
+
+pub async fn set_service_limits_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<SetServiceLimitsInput>,
+) -> impl IntoResponse {
+    let app_id = mongodb::bson::oid::ObjectId::parse_str(&body.app_id).unwrap();
+
+    let mut set = doc! {};
+    let mut unset = doc! {};
+    match body.rate_limit_per_second {
+        Some(value) => set.insert("rate_limit_per_second", value),
+        None => unset.insert("rate_limit_per_second", ""),
+    };
+    match body.rate_limit_burst {
+        Some(value) => set.insert("rate_limit_burst", value),
+        None => unset.insert("rate_limit_burst", ""),
+    };
+    match body.daily_quota {
+        Some(value) => set.insert("daily_quota", value),
+        None => unset.insert("daily_quota", ""),
+    };
+    let mut update = doc! {};
+    if !set.is_empty() {
+        update.insert("$set", set);
+    }
+    if !unset.is_empty() {
+        update.insert("$unset", unset);
+    }
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("services");
+    let service = collection
+        .find_one(doc! { "_id": app_id }, None)
+        .await
+        .unwrap();
+    let res = collection
+        .update_one(doc! { "_id": app_id }, update, None)
+        .await
+        .unwrap();
+
+    if res.matched_count == 0 {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Service not found",
+            "error_code": "service_not_found"
+        });
+
+        return Json(json_response);
+    }
+
+    let updated_service = collection
+        .find_one(doc! { "_id": app_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "set_service_limits",
+        "service",
+        Some(&body.app_id),
+        service.as_ref(),
+        updated_service.as_ref(),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    structs,
+    utils::{
+        audit_log::audit_log, is_valid_role_grant::is_valid_role_grant,
+        user::session::revoke_sessions::revoke_sessions,
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct SetUserRolesInput {
+    target_user_id: String,
+    roles: Vec<structs::RoleGrant>,
+}
+
+pub async fn set_user_roles_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<SetUserRolesInput>,
+) -> impl IntoResponse {
+    let target_user_id = body.target_user_id;
+    let roles = body.roles;
+
+    let db = &app_state.db;
+    for role in &roles {
+        if !is_valid_role_grant(db, role).await {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Invalid target for the {:?} scope: {:?}", role.scope, role.target),
+                "error_code": "invalid_role"
+            });
+
+            return Json(json_response);
+        }
+    }
+
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&target_user_id).unwrap();
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    collection
+        .update_one(
+            doc! { "_id": object_id },
+            doc! { "$set": { "roles": mongodb::bson::to_bson(&roles).unwrap() } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated_user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "set_user_roles",
+        "user",
+        Some(&target_user_id),
+        user.as_ref(),
+        updated_user.as_ref(),
+    )
+    .await;
+
+    // Issued tokens were granted under the previous roles
+    revoke_sessions(db, &target_user_id, None).await;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+    });
+
+    return Json(json_response);
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+pub async fn get_sinks_stats_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    // Sinks are configured in config.toml, their counters reset when the server restarts
+    let sinks: Vec<structs::SinkStats> = app_state
+        .sinks
+        .iter()
+        .map(|sink| sink.get_stats())
+        .collect();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "sinks": sinks,
+    }))
+}
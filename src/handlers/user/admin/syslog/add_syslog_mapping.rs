@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct AddSyslogMappingInput {
+    // Mappings without hostname or app_name match any value
+    hostname: Option<String>,
+    app_name: Option<String>,
+    app_id: String,
+    // Types to use for each severity, from 0 (emergency) to 7 (debug)
+    severity_types: Option<Vec<String>>,
+}
+
+pub async fn add_syslog_mapping_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<AddSyslogMappingInput>,
+) -> impl IntoResponse {
+    if body
+        .severity_types
+        .as_ref()
+        .is_some_and(|severity_types| severity_types.len() != 8)
+    {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "severity_types must contain one type per severity (8)",
+            "error_code": "invalid_mapping"
+        });
+
+        return Json(json_response);
+    }
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("syslog_mappings");
+    let mapping = doc! {
+        "hostname": body.hostname,
+        "app_name": body.app_name,
+        "app_id": body.app_id,
+        "severity_types": body.severity_types,
+    };
+    let res = collection.insert_one(mapping.clone(), None).await.unwrap();
+    let mapping_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_syslog_mapping",
+        "syslog_mapping",
+        Some(&mapping_id),
+        None,
+        Some(&mapping),
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+        "_id": mapping_id,
+    }))
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct DeleteSyslogMappingInput {
+    mapping_id: String,
+}
+
+pub async fn delete_syslog_mapping_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<DeleteSyslogMappingInput>,
+) -> impl IntoResponse {
+    let mapping_id = mongodb::bson::oid::ObjectId::parse_str(&body.mapping_id).unwrap();
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("syslog_mappings");
+    let deleted = collection
+        .find_one_and_delete(doc! { "_id": mapping_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "delete_syslog_mapping",
+        "syslog_mapping",
+        Some(&body.mapping_id),
+        deleted.as_ref(),
+        None,
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+pub async fn get_syslog_mappings_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    // get from mongodb
+    let mappings: Vec<structs::SyslogMapping> = get_syslog_mappings(app_state).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "mappings": mappings,
+    }))
+}
+
+async fn get_syslog_mappings(
+    app_state: Arc<AppState>,
+) -> Result<Vec<structs::SyslogMapping>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("syslog_mappings");
+
+    let mut cursor = collection.find(doc! {}, None).await?;
+
+    let mut result: Vec<structs::SyslogMapping> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let severity_types = doc.get_array("severity_types").ok().map(|types| {
+            types
+                .into_iter()
+                .map(|type_| type_.unwrap().as_str().unwrap_or_default().to_string())
+                .collect()
+        });
+        let mapping = structs::SyslogMapping {
+            _id: Some(_id.to_hex()),
+            hostname: doc.get_str("hostname").ok().map(|v| v.to_string()),
+            app_name: doc.get_str("app_name").ok().map(|v| v.to_string()),
+            app_id: doc.get_str("app_id").unwrap_or_default().to_string(),
+            severity_types,
+        };
+        result.push(mapping);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,3 @@
+pub mod add_syslog_mapping;
+pub mod delete_syslog_mapping;
+pub mod get_syslog_mappings;
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct UnlockUserInput {
+    // Clears the failed logins of the user, of the IP or of both
+    user_id: Option<String>,
+    ip: Option<String>,
+}
+
+pub async fn unlock_user_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<UnlockUserInput>,
+) -> impl IntoResponse {
+    let db = &app_state.db;
+    let mut keys: Vec<String> = Vec::new();
+
+    if let Some(user_id) = &body.user_id {
+        let user = match mongodb::bson::oid::ObjectId::parse_str(user_id) {
+            Ok(object_id) => db
+                .collection::<Document>("users")
+                .find_one(doc! { "_id": object_id }, None)
+                .await
+                .unwrap(),
+            Err(_) => None,
+        };
+        let Some(user) = user else {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "User not found",
+                "error_code": "user_not_found"
+            });
+
+            return Json(json_response);
+        };
+        keys.push(format!("username:{}", user.get_str("username").unwrap()));
+    }
+    if let Some(ip) = &body.ip {
+        keys.push(format!("ip:{}", ip));
+    }
+
+    if keys.is_empty() {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Specify a user_id or an ip",
+            "error_code": "missing_target"
+        });
+
+        return Json(json_response);
+    }
+
+    let collection: mongodb::Collection<Document> = db.collection("login_attempts");
+    let unlocked = collection
+        .delete_many(doc! { "_id": { "$in": &keys } }, None)
+        .await
+        .unwrap()
+        .deleted_count;
+
+    for key in &keys {
+        audit_log(
+            &app_state,
+            &admin,
+            "unlock_user",
+            "login_attempts",
+            Some(key),
+            None,
+            None,
+        )
+        .await;
+    }
+
+    Json(serde_json::json!({
+        "status": "success",
+        "unlocked": unlocked,
+    }))
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::AdminUser,
+    utils::{
+        audit_log::audit_log,
+        logs_service_side::webhook_mapping::{validate_mapping, WebhookMapping},
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct AddWebhookInput {
+    app_id: String,
+    name: String,
+    // "path" (secret in the URL, default) or "hmac" (signature of the body)
+    verification: Option<String>,
+    // Header holding the HMAC signature, defaults to X-Hub-Signature-256
+    signature_header: Option<String>,
+    mapping: WebhookMapping,
+}
+
+pub async fn add_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<AddWebhookInput>,
+) -> impl IntoResponse {
+    let verification = body.verification.unwrap_or("path".to_string());
+    if verification != "path" && verification != "hmac" {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "The verification must be \"path\" or \"hmac\"",
+            "error_code": "invalid_webhook"
+        });
+
+        return Json(json_response);
+    }
+
+    if let Err(err) = validate_mapping(&body.mapping) {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": err,
+            "error_code": "invalid_mapping"
+        });
+
+        return Json(json_response);
+    }
+
+    let mut secret = [0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    let secret = hex::encode(secret);
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("webhooks");
+    let webhook = doc! {
+        "app_id": body.app_id,
+        "name": body.name,
+        "verification": verification.clone(),
+        "signature_header": body.signature_header,
+        "secret": secret.clone(),
+        "mapping": mongodb::bson::to_bson(&body.mapping).unwrap(),
+    };
+    let res = collection.insert_one(webhook.clone(), None).await.unwrap();
+    let webhook_id = res.inserted_id.as_object_id().unwrap().to_hex();
+    audit_log(
+        &app_state,
+        &admin,
+        "add_webhook",
+        "webhook",
+        Some(&webhook_id),
+        None,
+        Some(&webhook),
+    )
+    .await;
+
+    // With HMAC verification the secret is only used to sign the payloads
+    let path = if verification == "hmac" {
+        format!("/webhooks/{}", webhook_id)
+    } else {
+        format!("/webhooks/{}/{}", webhook_id, secret)
+    };
+
+    Json(serde_json::json!({
+        "status": "success",
+        "_id": webhook_id,
+        "secret": secret,
+        "path": path,
+    }))
+}
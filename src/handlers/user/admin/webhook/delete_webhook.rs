@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::AdminUser, utils::audit_log::audit_log, AppState};
+
+#[derive(Deserialize)]
+pub struct DeleteWebhookInput {
+    webhook_id: String,
+}
+
+pub async fn delete_webhook_handler(
+    State(app_state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(body): Json<DeleteWebhookInput>,
+) -> impl IntoResponse {
+    let webhook_id = mongodb::bson::oid::ObjectId::parse_str(&body.webhook_id).unwrap();
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("webhooks");
+    let deleted = collection
+        .find_one_and_delete(doc! { "_id": webhook_id }, None)
+        .await
+        .unwrap();
+    audit_log(
+        &app_state,
+        &admin,
+        "delete_webhook",
+        "webhook",
+        Some(&body.webhook_id),
+        deleted.as_ref(),
+        None,
+    )
+    .await;
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
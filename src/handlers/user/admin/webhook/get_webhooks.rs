@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::{auth::extractors::AdminUser, structs, AppState};
+
+pub async fn get_webhooks_handler(
+    State(app_state): State<Arc<AppState>>,
+    _: AdminUser,
+) -> impl IntoResponse {
+    // get from mongodb
+    let webhooks: Vec<structs::Webhook> = get_webhooks(app_state).await.unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "webhooks": webhooks,
+    }))
+}
+
+async fn get_webhooks(
+    app_state: Arc<AppState>,
+) -> Result<Vec<structs::Webhook>, mongodb::error::Error> {
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("webhooks");
+
+    let mut cursor = collection.find(doc! {}, None).await?;
+
+    let mut result: Vec<structs::Webhook> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get("_id").unwrap().unwrap().as_object_id().unwrap();
+        let mapping = doc.get_document("mapping").unwrap();
+        let webhook = structs::Webhook {
+            _id: Some(_id.to_hex()),
+            app_id: doc.get_str("app_id").unwrap().to_string(),
+            name: doc.get_str("name").unwrap().to_string(),
+            verification: doc.get_str("verification").unwrap().to_string(),
+            signature_header: doc.get_str("signature_header").ok().map(|v| v.to_string()),
+            secret: doc.get_str("secret").unwrap().to_string(),
+            mapping: Bson::Document(Document::try_from(mapping).unwrap()).into_relaxed_extjson(),
+        };
+        result.push(webhook);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,3 @@
+pub mod add_webhook;
+pub mod delete_webhook;
+pub mod get_webhooks;
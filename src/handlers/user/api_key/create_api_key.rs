@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, DateTime, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::SessionUser,
+    structs,
+    utils::{
+        is_valid_role_grant::is_valid_role_grant, user::api_key::generate_api_key::generate_api_key,
+    },
+    AppState,
+};
+
+// Ten years, leave expires_in_days out for a key that never expires
+const MAX_EXPIRES_IN_DAYS: i64 = 3650;
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyInput {
+    name: String,
+    // The key never gets more than the roles of its owner
+    scopes: Vec<structs::RoleGrant>,
+    // Never expires when not specified
+    expires_in_days: Option<i64>,
+}
+
+// The key is only returned once
+pub async fn create_api_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+    Json(body): Json<CreateApiKeyInput>,
+) -> impl IntoResponse {
+    let db = &app_state.db;
+    for scope in &body.scopes {
+        if !is_valid_role_grant(db, scope).await {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Invalid target for the {:?} scope: {:?}", scope.scope, scope.target),
+                "error_code": "invalid_role"
+            });
+
+            return Json(json_response);
+        }
+    }
+
+    if body
+        .expires_in_days
+        .is_some_and(|days| !(1..=MAX_EXPIRES_IN_DAYS).contains(&days))
+    {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": format!("expires_in_days must be between 1 and {}", MAX_EXPIRES_IN_DAYS),
+            "error_code": "invalid_expiration"
+        });
+
+        return Json(json_response);
+    }
+
+    let (api_key, key_hash, prefix) = generate_api_key();
+    let now = chrono::Utc::now();
+    let expires_at = body
+        .expires_in_days
+        .map(|days| (now + chrono::Duration::days(days)).timestamp_millis());
+
+    let api_key_id = uuid::Uuid::new_v4().to_string();
+    let collection: mongodb::Collection<Document> = db.collection("api_keys");
+    collection
+        .insert_one(
+            doc! {
+                "_id": &api_key_id,
+                "user_id": &user.user_id,
+                "name": &body.name,
+                "prefix": &prefix,
+                "key_hash": key_hash,
+                "scopes": mongodb::bson::to_bson(&body.scopes).unwrap(),
+                "created_at": DateTime::from_millis(now.timestamp_millis()),
+                "expires_at": expires_at.map(DateTime::from_millis),
+                "last_used_at": null,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let details = structs::ApiKey {
+        _id: api_key_id,
+        name: body.name,
+        prefix,
+        scopes: body.scopes,
+        created_at: now.timestamp_millis(),
+        expires_at,
+        last_used_at: None,
+    };
+
+    Json(serde_json::json!({
+        "status": "success",
+        "api_key": api_key,
+        "details": details,
+    }))
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+
+use crate::{auth::extractors::SessionUser, structs, AppState};
+
+pub async fn get_api_keys_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+) -> impl IntoResponse {
+    let collection: mongodb::Collection<Document> = app_state.db.collection("api_keys");
+    let mut cursor = collection
+        .find(
+            doc! { "user_id": &user.user_id },
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "created_at": -1 })
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let mut api_keys: Vec<structs::ApiKey> = Vec::new();
+    while cursor.advance().await.unwrap() {
+        let doc = cursor.deserialize_current().unwrap();
+        let api_key = structs::ApiKey {
+            _id: doc.get_str("_id").unwrap().to_string(),
+            name: doc.get_str("name").unwrap().to_string(),
+            prefix: doc.get_str("prefix").unwrap().to_string(),
+            scopes: doc
+                .get_array("scopes")
+                .map(|scopes| {
+                    scopes
+                        .iter()
+                        .filter_map(|scope| mongodb::bson::from_bson(scope.clone()).ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            created_at: doc.get_datetime("created_at").unwrap().timestamp_millis(),
+            expires_at: doc
+                .get_datetime("expires_at")
+                .ok()
+                .map(|date| date.timestamp_millis()),
+            last_used_at: doc
+                .get_datetime("last_used_at")
+                .ok()
+                .map(|date| date.timestamp_millis()),
+        };
+        api_keys.push(api_key);
+    }
+
+    Json(serde_json::json!({
+        "status": "success",
+        "api_keys": api_keys,
+    }))
+}
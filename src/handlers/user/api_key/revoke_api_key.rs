@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{auth::extractors::SessionUser, AppState};
+
+#[derive(Deserialize)]
+pub struct RevokeApiKeyInput {
+    api_key_id: String,
+}
+
+pub async fn revoke_api_key_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+    Json(body): Json<RevokeApiKeyInput>,
+) -> impl IntoResponse {
+    let collection: mongodb::Collection<Document> = app_state.db.collection("api_keys");
+    let deleted = collection
+        .delete_one(
+            doc! { "_id": &body.api_key_id, "user_id": &user.user_id },
+            None,
+        )
+        .await
+        .unwrap()
+        .deleted_count;
+
+    if deleted == 0 {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "API key not found",
+            "error_code": "api_key_not_found"
+        });
+
+        return Json(json_response);
+    }
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
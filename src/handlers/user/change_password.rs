@@ -5,44 +5,57 @@ use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 
 use crate::{
+    auth::extractors::SessionUser,
     utils::{
-        check_auth_token::check_auth_token, get_token_data::get_token_data,
         hash_password::hash_password,
+        user::session::revoke_sessions::revoke_sessions,
+        verify_password::{verify_password, PasswordCheck},
     },
     AppState,
 };
 
 #[derive(Deserialize)]
 pub struct ChangePasswordInput {
-    token: String,
+    current_password: String,
     new_password: String,
 }
 
 pub async fn change_password_handler(
     State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
     Json(body): Json<ChangePasswordInput>,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
+    let user_id = user.user_id.clone();
+
+    let db = &app_state.db;
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user_id).unwrap();
+    let collection: mongodb::Collection<Document> = db.collection("users");
+
+    // A stolen token alone must not be enough to take over the account
+    let current_user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    let check = match &current_user {
+        Some(current_user) => verify_password(
+            &app_state.conf,
+            &body.current_password,
+            current_user.get_str("password").unwrap_or_default(),
+        ),
+        None => PasswordCheck::Invalid,
+    };
+    if check == PasswordCheck::Invalid {
         let json_response = serde_json::json!({
             "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
+            "message": "Invalid current password",
+            "error_code": "invalid_credentials"
         });
 
         return Json(json_response);
     }
 
-    let token_data = get_token_data(app_state.clone(), token);
-    let user_id = token_data.user_id;
-
     let new_password = body.new_password;
     let password_hash = hash_password(app_state.clone(), new_password);
-
-    let db = &app_state.db;
-    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user_id).unwrap();
-    let collection: mongodb::Collection<Document> = db.collection("users");
     collection
         .update_one(
             doc! { "_id": object_id },
@@ -52,6 +65,9 @@ pub async fn change_password_handler(
         .await
         .unwrap();
 
+    // Other devices have to log in again with the new password
+    revoke_sessions(db, &user_id, Some(&user.session_id)).await;
+
     let json_response = serde_json::json!({
         "status": "success",
     });
@@ -1,19 +1,9 @@
-use std::sync::Arc;
+use axum::{response::IntoResponse, Json};
 
-use axum::{extract::State, response::IntoResponse, Json};
+use crate::auth::extractors::AuthUser;
 
-use crate::AppState;
-
-use crate::{structs::AuthTokenJSON, utils::check_auth_token::check_auth_token};
-
-pub async fn check_auth_token_handler(
-    State(app_state): State<Arc<AppState>>,
-    Json(body): Json<AuthTokenJSON>,
-) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state, token);
-
-    if valid {
+pub async fn check_auth_token_handler(user: Option<AuthUser>) -> impl IntoResponse {
+    if user.is_some() {
         let json_response = serde_json::json!({
             "status": "success",
         });
@@ -3,30 +3,14 @@ use std::sync::Arc;
 use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 
-use crate::{
-    structs,
-    utils::{check_auth_token::check_auth_token, get_token_data::get_token_data},
-    AppState,
-};
+use crate::{auth::extractors::AuthUser, utils::get_user_roles::get_user_roles, AppState};
 
 pub async fn get_permissions_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<structs::AuthTokenJSON>,
+    user: AuthUser,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
-    let token_data = get_token_data(app_state.clone(), token.clone());
-    let user_id = mongodb::bson::oid::ObjectId::parse_str(&token_data.user_id).unwrap();
+    let roles = get_user_roles(app_state.clone(), &user.user_id).await;
+    let user_id = mongodb::bson::oid::ObjectId::parse_str(&user.user_id).unwrap();
 
     // get from mongodb
     let db = &app_state.db;
@@ -40,5 +24,6 @@ pub async fn get_permissions_handler(
     return Json(serde_json::json!({
         "status": "success",
         "permissions": permissions,
+        "roles": roles,
     }));
 }
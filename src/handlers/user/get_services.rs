@@ -3,26 +3,21 @@ use std::sync::Arc;
 use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 
-use crate::{structs, utils::check_auth_token::check_auth_token, AppState};
+use crate::{
+    auth::extractors::AuthUser,
+    structs::{self, Role},
+    utils::get_accessible_services::get_accessible_services,
+    AppState,
+};
 
 pub async fn get_services_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<structs::AuthTokenJSON>,
+    user: AuthUser,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
+    let app_ids = get_accessible_services(app_state.clone(), &user, Role::Viewer).await;
 
     // get from mongodb
-    let services: Vec<structs::Service> = get_services(app_state).await.unwrap();
+    let services: Vec<structs::Service> = get_services(app_state, app_ids).await.unwrap();
 
     return Json(serde_json::json!({
         "status": "success",
@@ -32,11 +27,23 @@ pub async fn get_services_handler(
 
 async fn get_services(
     app_state: Arc<AppState>,
+    app_ids: Option<Vec<String>>,
 ) -> Result<Vec<structs::Service>, mongodb::error::Error> {
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("services");
 
-    let mut cursor = collection.find(doc! {}, None).await?;
+    // Only the services the user has a role on
+    let filter = match app_ids {
+        Some(app_ids) => {
+            let object_ids: Vec<_> = app_ids
+                .iter()
+                .filter_map(|app_id| mongodb::bson::oid::ObjectId::parse_str(app_id).ok())
+                .collect();
+            doc! { "_id": { "$in": object_ids } }
+        }
+        None => doc! {},
+    };
+    let mut cursor = collection.find(filter, None).await?;
 
     let mut result: Vec<structs::Service> = Vec::new();
     while cursor.advance().await? {
@@ -46,6 +53,7 @@ async fn get_services(
         let service = structs::Service {
             _id: Some(_id.to_hex()),
             app_name: Some(app_name.to_string()),
+            group: doc.get_str("group").ok().map(|group| group.to_string()),
         };
         result.push(service);
     }
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+
+use crate::{
+    auth::extractors::SessionUser, structs, utils::user::session::get_sessions::get_sessions,
+    AppState,
+};
+
+pub async fn get_sessions_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+) -> impl IntoResponse {
+    let sessions: Vec<structs::Session> =
+        get_sessions(&app_state.db, &user.user_id, Some(&user.session_id))
+            .await
+            .unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+        "sessions": sessions,
+    }))
+}
@@ -3,24 +3,12 @@ use std::sync::Arc;
 use axum::{extract::State, response::IntoResponse, Json};
 use mongodb::bson::{doc, Document};
 
-use crate::{structs, utils::check_auth_token::check_auth_token, AppState};
+use crate::{auth::extractors::AuthUser, structs, AppState};
 
 pub async fn get_types_handler(
     State(app_state): State<Arc<AppState>>,
-    Json(body): Json<structs::AuthTokenJSON>,
+    _: AuthUser,
 ) -> impl IntoResponse {
-    let token = body.token;
-    let valid = check_auth_token(app_state.clone(), token.clone());
-    if !valid {
-        let json_response = serde_json::json!({
-            "status": "error",
-            "message": "Invalid token or token expired",
-            "error_code": "invalid_token"
-        });
-
-        return Json(json_response);
-    }
-
     // get from mongodb
     let types: Vec<structs::Type> = get_types(app_state).await.unwrap();
 
@@ -59,6 +47,11 @@ async fn get_types(app_state: Arc<AppState>) -> Result<Vec<structs::Type>, mongo
         let parents: Vec<String> = parents_cursor
             .map(|parent| parent.unwrap().as_str().unwrap().to_string())
             .collect();
+        let notify_new_issues_only = doc
+            .get("notify_new_issues_only")
+            .unwrap()
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
 
         let type_ = structs::Type {
             _id: Some(_id.to_hex()),
@@ -68,6 +61,7 @@ async fn get_types(app_state: Arc<AppState>) -> Result<Vec<structs::Type>, mongo
             importance: importance,
             notifications: notifcations,
             parents: parents,
+            notify_new_issues_only,
         };
         result.push(type_);
     }
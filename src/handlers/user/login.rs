@@ -1,19 +1,35 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
-use argon2::{
-    password_hash::{PasswordHasher, SaltString},
-    Argon2,
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
 };
-
-use axum::{extract::State, response::IntoResponse, Json};
-use jsonwebtoken::{encode, EncodingKey, Header};
-use mongodb::bson::{doc, Document};
+use axum_extra::extract::cookie::CookieJar;
+use mongodb::bson::{doc, DateTime, Document};
 use serde::Deserialize;
 
-use crate::structs;
+use crate::auth::tokens::start_session;
+use crate::utils::{
+    has_permission::has_permission,
+    hash_password::hash_password,
+    user::{
+        login_attempts::{
+            get_client_ip::get_client_ip, record_login_failure::record_login_failure,
+            release_login_attempt::release_login_attempt,
+            reserve_login_attempt::reserve_login_attempt,
+        },
+        totp::is_admin_2fa_required::is_admin_2fa_required,
+    },
+    verify_password::{verify_password, PasswordCheck},
+};
 
 use crate::AppState;
 
+// Time given to enter the second factor
+const MFA_CHALLENGE_MINUTES: i64 = 5;
+
 #[derive(Deserialize)]
 pub struct LoginInput {
     username: String,
@@ -21,64 +37,158 @@ pub struct LoginInput {
 }
 pub async fn login_handler(
     State(app_state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    jar: CookieJar,
+    headers: HeaderMap,
     Json(body): Json<LoginInput>,
 ) -> impl IntoResponse {
     let config = app_state.conf.clone();
 
     let username = body.username;
     let password = body.password;
-    // hash password
-    let salt = SaltString::encode_b64(&config.security.password_salt.as_bytes()).unwrap();
-    let argon2 = Argon2::default();
-    let password_hash = argon2
-        .hash_password(password.as_bytes(), &salt)
-        .unwrap()
-        .to_string();
-    // check in mongodb
     let db = &app_state.db;
-    let user: Option<Document> = db
-        .collection("users")
-        .find_one(
-            doc! { "username": username, "password": password_hash },
-            None,
-        )
+
+    // With single sign-on, local passwords can be kept for root only
+    let local_login_disabled = config
+        .oidc
+        .as_ref()
+        .is_some_and(|oidc| oidc.disable_local_login);
+    if local_login_disabled && username != "root" {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Local login is disabled, use single sign-on",
+            "error_code": "local_login_disabled"
+        });
+
+        return (jar, Json(json_response));
+    }
+
+    // Refused before checking the password, the hash is slow on purpose.
+    // The attempt is counted right away and given back if the password is valid.
+    let attempt_keys = [
+        format!("username:{}", username),
+        format!("ip:{}", get_client_ip(&config, &headers, addr)),
+    ];
+    let mut attempts = Vec::new();
+    for key in &attempt_keys {
+        let retry_after = match reserve_login_attempt(&app_state, key).await {
+            Ok(failures) => {
+                attempts.push((key, failures));
+                continue;
+            }
+            Err(retry_after) => retry_after,
+        };
+        for (key, _) in &attempts {
+            release_login_attempt(db, key).await;
+        }
+
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Too many failed attempts, retry in {} seconds", retry_after),
+            "error_code": "too_many_attempts",
+            "retry_after": retry_after,
+        });
+
+        return (jar, Json(json_response));
+    }
+
+    // check in mongodb
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user: Option<Document> = collection
+        .find_one(doc! { "username": username }, None)
         .await
         .unwrap();
 
-    // if user is not found
-    if user.is_none() {
+    let check = match &user {
+        Some(user) => verify_password(
+            &config,
+            &password,
+            user.get_str("password").unwrap_or_default(),
+        ),
+        None => PasswordCheck::Invalid,
+    };
+
+    // if user is not found or the password is wrong
+    if check == PasswordCheck::Invalid {
+        for (key, failures) in &attempts {
+            record_login_failure(&app_state, key, *failures).await;
+        }
+
         let json_response = serde_json::json!({
             "status": "error",
             "message": "Invalid username or password",
             "error_code": "invalid_credentials"
         });
 
-        return Json(json_response);
+        return (jar, Json(json_response));
     }
-    // if user is found
-    let jwt_secret = &config.jwt.user_secret;
-    let jwt_user_max_age = &config.jwt.user_max_age;
+    let user = user.unwrap();
 
-    // get date in 'jwt_user_max_age' days
-    let date = chrono::Utc::now() + chrono::Duration::days(*jwt_user_max_age as i64);
+    // The IP counter is kept, a valid account must not clear it for other usernames
+    release_login_attempt(db, &attempt_keys[1]).await;
 
-    let claims = structs::JwtUserClaims {
-        exp: date.timestamp() as usize,
-        user_id: user.unwrap().get_object_id("_id").unwrap().to_hex(),
-    };
+    // Upgrade legacy hashes now that we know the password
+    if check == PasswordCheck::NeedsRehash {
+        let password_hash = hash_password(app_state.clone(), password);
+        collection
+            .update_one(
+                doc! { "_id": user.get_object_id("_id").unwrap() },
+                doc! { "$set": { "password": password_hash } },
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let user_id = user.get_object_id("_id").unwrap().to_hex();
+
+    // The session is only opened once the second factor is checked by /login_2fa,
+    // which also clears the username counter once it succeeds
+    if user.get_bool("totp_enabled").unwrap_or(false) {
+        release_login_attempt(db, &attempt_keys[0]).await;
+
+        let mfa_token = uuid::Uuid::new_v4().to_string();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(MFA_CHALLENGE_MINUTES);
+        let challenges: mongodb::Collection<Document> = db.collection("mfa_challenges");
+        challenges
+            .insert_one(
+                doc! {
+                    "_id": mfa_token.clone(),
+                    "user_id": &user_id,
+                    "attempt_key": &attempt_keys[0],
+                    "attempts": 0,
+                    "expires_at": DateTime::from_millis(expires_at.timestamp_millis()),
+                },
+                None,
+            )
+            .await
+            .unwrap();
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(jwt_secret.as_ref()),
-    )
-    .unwrap();
+        let json_response = serde_json::json!({
+            "status": "mfa_required",
+            "mfa_token": mfa_token,
+        });
+
+        return (jar, Json(json_response));
+    }
+
+    let attempts: mongodb::Collection<Document> = db.collection("login_attempts");
+    attempts
+        .delete_one(doc! { "_id": &attempt_keys[0] }, None)
+        .await
+        .unwrap();
+
+    // Administrators can still log in to enroll, admin routes are refused until then
+    let setup_required = is_admin_2fa_required()
+        && has_permission(
+            user_id.clone(),
+            "administrator".to_string(),
+            app_state.clone(),
+        )
+        .await;
 
-    let json_response = serde_json::json!({
-        "status": "success",
-        "token": token,
-        "max_age": jwt_user_max_age
-    });
+    let (jar, mut json_response) = start_session(&app_state, jar, &headers, user_id).await;
+    json_response["two_factor_setup_required"] = serde_json::json!(setup_required);
 
-    Json(json_response)
+    (jar, Json(json_response))
 }
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, Json};
+use axum_extra::extract::cookie::CookieJar;
+use mongodb::bson::{doc, DateTime, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::tokens::start_session,
+    utils::user::{
+        login_attempts::{
+            record_login_failure::record_login_failure,
+            reserve_login_attempt::reserve_login_attempt,
+        },
+        totp::check_second_factor::check_second_factor,
+    },
+    AppState,
+};
+
+// Wrong codes allowed before the challenge is dropped and the password asked again
+const MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Deserialize)]
+pub struct Login2faInput {
+    mfa_token: String,
+    // TOTP code or recovery code
+    code: String,
+}
+
+// Second login step for users with TOTP enabled
+pub async fn login_2fa_handler(
+    State(app_state): State<Arc<AppState>>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Json(body): Json<Login2faInput>,
+) -> impl IntoResponse {
+    let db = &app_state.db;
+    let challenges: mongodb::Collection<Document> = db.collection("mfa_challenges");
+    let challenge = challenges
+        .find_one_and_update(
+            doc! { "_id": &body.mfa_token, "expires_at": { "$gt": DateTime::now() } },
+            doc! { "$inc": { "attempts": 1 } },
+            None,
+        )
+        .await
+        .unwrap();
+    let Some(challenge) = challenge else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Invalid or expired login attempt, please log in again",
+            "error_code": "invalid_mfa_token"
+        });
+
+        return (jar, Json(json_response));
+    };
+
+    if challenge.get_i32("attempts").unwrap_or(0) >= MAX_ATTEMPTS {
+        challenges
+            .delete_one(doc! { "_id": &body.mfa_token }, None)
+            .await
+            .unwrap();
+
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Too many invalid codes, please log in again",
+            "error_code": "too_many_attempts"
+        });
+
+        return (jar, Json(json_response));
+    }
+
+    // Wrong codes count against the username like wrong passwords, across challenges
+    let attempt_key = challenge
+        .get_str("attempt_key")
+        .unwrap_or_default()
+        .to_string();
+    let failures = match reserve_login_attempt(&app_state, &attempt_key).await {
+        Ok(failures) => failures,
+        Err(retry_after) => {
+            challenges
+                .delete_one(doc! { "_id": &body.mfa_token }, None)
+                .await
+                .unwrap();
+
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Too many failed attempts, retry in {} seconds", retry_after),
+                "error_code": "too_many_attempts",
+                "retry_after": retry_after,
+            });
+
+            return (jar, Json(json_response));
+        }
+    };
+
+    let user_id = challenge.get_str("user_id").unwrap().to_string();
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user_id).unwrap();
+    let users: mongodb::Collection<Document> = db.collection("users");
+    let user = users
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    let valid = match &user {
+        Some(user) => check_second_factor(db, user, &body.code).await,
+        None => false,
+    };
+    if !valid {
+        record_login_failure(&app_state, &attempt_key, failures).await;
+
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Invalid code",
+            "error_code": "invalid_code"
+        });
+
+        return (jar, Json(json_response));
+    }
+
+    challenges
+        .delete_one(doc! { "_id": &body.mfa_token }, None)
+        .await
+        .unwrap();
+    let attempts: mongodb::Collection<Document> = db.collection("login_attempts");
+    attempts
+        .delete_one(doc! { "_id": &attempt_key }, None)
+        .await
+        .unwrap();
+
+    let (jar, json_response) = start_session(&app_state, jar, &headers, user_id).await;
+
+    (jar, Json(json_response))
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_extra::extract::cookie::CookieJar;
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    auth::{extractors::SessionUser, tokens::clear_tokens},
+    AppState,
+};
+
+// Revokes the session of the token and clears the cookies
+pub async fn logout_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: Option<SessionUser>,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    if let Some(user) = user {
+        let collection: mongodb::Collection<Document> = app_state.db.collection("sessions");
+        collection
+            .delete_one(doc! { "_id": &user.session_id }, None)
+            .await
+            .unwrap();
+    }
+
+    let json_response = serde_json::json!({
+        "status": "success",
+    });
+
+    (clear_tokens(jar), Json(json_response))
+}
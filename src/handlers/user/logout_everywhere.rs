@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_extra::extract::cookie::CookieJar;
+
+use crate::{
+    auth::{extractors::SessionUser, tokens::clear_tokens},
+    utils::user::session::revoke_sessions::revoke_sessions,
+    AppState,
+};
+
+// Revokes every session of the user, including the current one
+pub async fn logout_everywhere_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let revoked = revoke_sessions(&app_state.db, &user.user_id, None).await;
+
+    let json_response = serde_json::json!({
+        "status": "success",
+        "revoked": revoked,
+    });
+
+    (clear_tokens(jar), Json(json_response))
+}
@@ -1,7 +1,15 @@
 pub mod admin;
+pub mod api_key;
 pub mod change_password;
 pub mod check_auth_token;
 pub mod get_permissions;
 pub mod get_services;
+pub mod get_sessions;
 pub mod get_types;
 pub mod login;
+pub mod login_2fa;
+pub mod logout;
+pub mod logout_everywhere;
+pub mod oidc;
+pub mod refresh;
+pub mod totp;
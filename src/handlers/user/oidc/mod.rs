@@ -0,0 +1,2 @@
+pub mod oidc_callback;
+pub mod oidc_login;
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Redirect,
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use mongodb::bson::{doc, DateTime, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::tokens::start_session,
+    utils::user::oidc::{
+        exchange_oidc_code::exchange_oidc_code,
+        get_oidc_discovery::get_oidc_discovery,
+        provision_oidc_user::provision_oidc_user,
+        state_cookie::{check_state_cookie, clear_state_cookie},
+        validate_id_token::validate_id_token,
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    // Set by the provider when the login failed or was refused
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+fn oidc_error(message: String, error_code: &str) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "error",
+        "message": message,
+        "error_code": error_code
+    }))
+}
+
+// Single sign-on users rely on the provider for their second factor, including administrators
+// when two-factor authentication is required
+pub async fn oidc_callback_handler(
+    State(app_state): State<Arc<AppState>>,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<(CookieJar, Redirect), Json<serde_json::Value>> {
+    let Some(oidc) = &app_state.conf.oidc else {
+        return Err(oidc_error(
+            "Single sign-on is not configured".to_string(),
+            "oidc_disabled",
+        ));
+    };
+
+    if let Some(error) = query.error {
+        return Err(oidc_error(
+            query.error_description.unwrap_or(error),
+            "oidc_login_failed",
+        ));
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return Err(oidc_error(
+            "Missing code or state".to_string(),
+            "invalid_oidc_callback",
+        ));
+    };
+
+    // Login CSRF: a state started in another browser must not log this one in
+    if !check_state_cookie(&jar, &state) {
+        return Err(oidc_error(
+            "The login was started from another browser, please try again".to_string(),
+            "invalid_oidc_state",
+        ));
+    }
+
+    // A state can only be used once
+    let collection: mongodb::Collection<Document> = app_state.db.collection("oidc_states");
+    let login = collection
+        .find_one_and_delete(
+            doc! { "_id": &state, "expires_at": { "$gt": DateTime::now() } },
+            None,
+        )
+        .await
+        .unwrap();
+    let Some(login) = login else {
+        return Err(oidc_error(
+            "Unknown or expired login, please try again".to_string(),
+            "invalid_oidc_state",
+        ));
+    };
+    let nonce = login.get_str("nonce").unwrap();
+    let code_verifier = login.get_str("code_verifier").unwrap();
+
+    let discovery = get_oidc_discovery(oidc)
+        .await
+        .map_err(|err| oidc_error(err, "oidc_unavailable"))?;
+    let id_token = exchange_oidc_code(oidc, &discovery, &code, code_verifier)
+        .await
+        .map_err(|err| oidc_error(err, "oidc_login_failed"))?;
+    let claims = validate_id_token(oidc, &discovery, &id_token, nonce)
+        .await
+        .map_err(|err| oidc_error(err, "invalid_id_token"))?;
+    let user_id = provision_oidc_user(&app_state, oidc, &claims)
+        .await
+        .map_err(|err| oidc_error(err, "username_taken"))?;
+
+    // The tokens are in the cookies, the dashboard doesn't need the JSON response
+    let jar = clear_state_cookie(jar);
+    let (jar, _) = start_session(&app_state, jar, &headers, user_id).await;
+
+    Ok((jar, Redirect::to(&oidc.post_login_redirect)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use axum_extra::extract::cookie::Cookie;
+
+    use super::*;
+    use crate::utils::user::oidc::state_cookie::STATE_COOKIE;
+
+    // Any query of the database fails fast, the state cookie is checked before
+    async fn app_state() -> Arc<AppState> {
+        let conf = toml::from_str(
+            r#"
+            [database]
+            url = "mongodb://127.0.0.1:1"
+            name = "watchtower"
+
+            [jwt]
+            user_secret = "user_secret"
+            service_secret = "service_secret"
+
+            [security]
+            root_user_password = "root"
+            auto_update_root_user = false
+
+            [connections]
+            telegram_token = ""
+
+            [oidc]
+            issuer_url = "https://idp.example.com"
+            client_id = "watchtower"
+            redirect_uri = "https://watchtower.example.com/oidc/callback"
+            "#,
+        )
+        .unwrap();
+        let client =
+            mongodb::Client::with_uri_str("mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=100")
+                .await
+                .unwrap();
+        Arc::new(AppState {
+            conf,
+            db: client.database("watchtower"),
+            rate_limits: Mutex::new(HashMap::new()),
+            pipelines: Mutex::new(HashMap::new()),
+            redaction_rules: Mutex::new(HashMap::new()),
+            sinks: Vec::new(),
+        })
+    }
+
+    async fn callback(jar: CookieJar) -> serde_json::Value {
+        let query = OidcCallbackQuery {
+            code: Some("code".to_string()),
+            state: Some("state".to_string()),
+            error: None,
+            error_description: None,
+        };
+        let result = oidc_callback_handler(
+            State(app_state().await),
+            jar,
+            HeaderMap::new(),
+            Query(query),
+        )
+        .await;
+        let Err(Json(json_response)) = result else {
+            panic!("the callback was accepted");
+        };
+        json_response
+    }
+
+    #[tokio::test]
+    async fn rejects_callback_without_state_cookie() {
+        let json_response = callback(CookieJar::new()).await;
+        assert_eq!(json_response["error_code"], "invalid_oidc_state");
+    }
+
+    #[tokio::test]
+    async fn rejects_state_cookie_of_another_login() {
+        let jar = CookieJar::new().add(Cookie::new(STATE_COOKIE, "another login"));
+        let json_response = callback(jar).await;
+        assert_eq!(json_response["error_code"], "invalid_oidc_state");
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::Redirect, Json};
+use axum_extra::extract::cookie::CookieJar;
+use base64::Engine;
+use mongodb::bson::{doc, DateTime, Document};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    utils::user::oidc::{get_oidc_discovery::get_oidc_discovery, state_cookie::set_state_cookie},
+    AppState,
+};
+
+// Time given to log in at the provider
+const STATE_MINUTES: i64 = 10;
+
+fn random_string() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// Redirects the browser to the provider, the flow ends on /oidc/callback
+pub async fn oidc_login_handler(
+    State(app_state): State<Arc<AppState>>,
+    jar: CookieJar,
+) -> Result<(CookieJar, Redirect), Json<serde_json::Value>> {
+    let Some(oidc) = &app_state.conf.oidc else {
+        return Err(Json(serde_json::json!({
+            "status": "error",
+            "message": "Single sign-on is not configured",
+            "error_code": "oidc_disabled"
+        })));
+    };
+
+    let discovery = get_oidc_discovery(oidc).await.map_err(|err| {
+        Json(serde_json::json!({
+            "status": "error",
+            "message": err,
+            "error_code": "oidc_unavailable"
+        }))
+    })?;
+
+    // PKCE, only the hash of the verifier is sent to the provider
+    let state = random_string();
+    let nonce = random_string();
+    let code_verifier = random_string();
+    let code_challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(code_verifier.as_bytes()));
+
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(STATE_MINUTES);
+    let collection: mongodb::Collection<Document> = app_state.db.collection("oidc_states");
+    collection
+        .insert_one(
+            doc! {
+                "_id": &state,
+                "nonce": &nonce,
+                "code_verifier": code_verifier,
+                "expires_at": DateTime::from_millis(expires_at.timestamp_millis()),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let Ok(mut url) = reqwest::Url::parse(&discovery.authorization_endpoint) else {
+        return Err(Json(serde_json::json!({
+            "status": "error",
+            "message": "Invalid authorization endpoint",
+            "error_code": "oidc_unavailable"
+        })));
+    };
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &oidc.client_id)
+        .append_pair("redirect_uri", &oidc.redirect_uri)
+        .append_pair("scope", &oidc.scopes.join(" "))
+        .append_pair("state", &state)
+        .append_pair("nonce", &nonce)
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256");
+
+    // The callback only accepts the state from the browser that was sent to the provider
+    let jar = set_state_cookie(&app_state.conf, jar, &state, STATE_MINUTES);
+
+    Ok((jar, Redirect::to(url.as_str())))
+}
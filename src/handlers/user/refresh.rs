@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_extra::extract::cookie::CookieJar;
+use serde::Deserialize;
+
+use crate::{
+    auth::tokens::{clear_tokens, issue_tokens, REFRESH_COOKIE},
+    utils::user::session::rotate_refresh_token::{rotate_refresh_token, RefreshCheck},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RefreshInput {
+    refresh_token: Option<String>,
+}
+
+// Exchanges a refresh token, from the body or the cookie, for a new pair of tokens
+pub async fn refresh_handler(
+    State(app_state): State<Arc<AppState>>,
+    jar: CookieJar,
+    body: Option<Json<RefreshInput>>,
+) -> impl IntoResponse {
+    let refresh_token = body.and_then(|Json(body)| body.refresh_token).or_else(|| {
+        jar.get(REFRESH_COOKIE)
+            .map(|cookie| cookie.value().to_string())
+    });
+    let Some(refresh_token) = refresh_token else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Missing refresh token",
+            "error_code": "missing_refresh_token"
+        });
+
+        return (jar, Json(json_response));
+    };
+
+    match rotate_refresh_token(&app_state.db, &refresh_token).await {
+        RefreshCheck::Rotated {
+            user_id,
+            session_id,
+            refresh_token,
+            expires_at,
+        } => {
+            let (jar, json_response) = issue_tokens(
+                &app_state.conf,
+                jar,
+                user_id,
+                session_id,
+                refresh_token,
+                expires_at,
+            );
+            (jar, Json(json_response))
+        }
+        RefreshCheck::Reused => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "Refresh token already used, the session has been revoked",
+                "error_code": "refresh_token_reused"
+            });
+
+            (clear_tokens(jar), Json(json_response))
+        }
+        RefreshCheck::Invalid => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "Invalid refresh token or session expired",
+                "error_code": "invalid_refresh_token"
+            });
+
+            (clear_tokens(jar), Json(json_response))
+        }
+    }
+}
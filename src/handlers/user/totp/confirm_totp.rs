@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::SessionUser,
+    utils::user::totp::{
+        generate_recovery_codes::generate_recovery_codes, verify_totp::verify_totp,
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ConfirmTotpInput {
+    code: String,
+}
+
+// Enables TOTP and returns the recovery codes, they are not shown again
+pub async fn confirm_totp_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+    Json(body): Json<ConfirmTotpInput>,
+) -> impl IntoResponse {
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user.user_id).unwrap();
+    let collection: mongodb::Collection<Document> = app_state.db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let Ok(secret) = user.get_str("totp_pending_secret") else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "No pending enrollment, call /enroll_totp first",
+            "error_code": "no_pending_totp"
+        });
+
+        return Json(json_response);
+    };
+
+    let Some(step) = verify_totp(secret, &body.code, None) else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Invalid code",
+            "error_code": "invalid_code"
+        });
+
+        return Json(json_response);
+    };
+
+    // Concurrent confirmations of the same enrollment only succeed once
+    let (recovery_codes, hashes) = generate_recovery_codes();
+    let res = collection
+        .update_one(
+            doc! { "_id": object_id, "totp_pending_secret": secret },
+            doc! {
+                "$set": {
+                    "totp_enabled": true,
+                    "totp_secret": secret,
+                    "totp_last_step": step,
+                    "recovery_codes": hashes,
+                },
+                "$unset": { "totp_pending_secret": "" },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    if res.modified_count == 0 {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "No pending enrollment, call /enroll_totp first",
+            "error_code": "no_pending_totp"
+        });
+
+        return Json(json_response);
+    }
+
+    Json(serde_json::json!({
+        "status": "success",
+        "recovery_codes": recovery_codes,
+    }))
+}
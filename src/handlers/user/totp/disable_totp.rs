@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+
+use crate::{
+    auth::extractors::SessionUser, utils::user::totp::check_second_factor::check_second_factor,
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct DisableTotpInput {
+    // TOTP code or recovery code
+    code: String,
+}
+
+pub async fn disable_totp_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+    Json(body): Json<DisableTotpInput>,
+) -> impl IntoResponse {
+    let db = &app_state.db;
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user.user_id).unwrap();
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap()
+        .unwrap();
+
+    if !user.get_bool("totp_enabled").unwrap_or(false) {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Two-factor authentication is not enabled",
+            "error_code": "totp_not_enabled"
+        });
+
+        return Json(json_response);
+    }
+
+    if !check_second_factor(db, &user, &body.code).await {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Invalid code",
+            "error_code": "invalid_code"
+        });
+
+        return Json(json_response);
+    }
+
+    collection
+        .update_one(
+            doc! { "_id": object_id },
+            doc! {
+                "$set": { "totp_enabled": false },
+                "$unset": {
+                    "totp_secret": "",
+                    "totp_last_step": "",
+                    "recovery_codes": "",
+                },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    Json(serde_json::json!({
+        "status": "success",
+    }))
+}
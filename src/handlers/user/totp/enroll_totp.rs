@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    auth::extractors::SessionUser,
+    utils::user::totp::generate_totp_secret::{generate_totp_secret, get_totp_uri},
+    AppState,
+};
+
+// Generates a secret, TOTP is only enabled once a code is confirmed with /confirm_totp
+pub async fn enroll_totp_handler(
+    State(app_state): State<Arc<AppState>>,
+    user: SessionUser,
+) -> impl IntoResponse {
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(&user.user_id).unwrap();
+    let collection: mongodb::Collection<Document> = app_state.db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap()
+        .unwrap();
+
+    if user.get_bool("totp_enabled").unwrap_or(false) {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Two-factor authentication is already enabled",
+            "error_code": "totp_already_enabled"
+        });
+
+        return Json(json_response);
+    }
+
+    let secret = generate_totp_secret();
+    collection
+        .update_one(
+            doc! { "_id": object_id },
+            doc! { "$set": { "totp_pending_secret": &secret } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let uri = get_totp_uri(&secret, user.get_str("username").unwrap_or_default());
+
+    Json(serde_json::json!({
+        "status": "success",
+        "secret": secret,
+        "uri": uri,
+    }))
+}
@@ -0,0 +1,3 @@
+pub mod confirm_totp;
+pub mod disable_totp;
+pub mod enroll_totp;
@@ -0,0 +1,234 @@
+use prost::Message;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    loki::proto::PushRequest, structs, utils::logs_service_side::severity::get_severity_type,
+};
+
+// Labels checked in order to find the type of a stream when it has no `type` label
+const LEVEL_LABELS: [&str; 4] = ["level", "detected_level", "severity", "lvl"];
+
+pub struct LokiStream {
+    pub labels: Map<String, Value>,
+    pub entries: Vec<LokiEntry>,
+}
+
+pub struct LokiEntry {
+    pub timestamp_ns: i64,
+    pub line: String,
+    pub metadata: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct PushRequestJson {
+    streams: Vec<StreamJson>,
+}
+
+#[derive(Deserialize)]
+struct StreamJson {
+    stream: Map<String, Value>,
+    values: Vec<EntryJson>,
+}
+
+// [<unix epoch in nanoseconds>, <line>] with optional structured metadata
+#[derive(Deserialize)]
+struct EntryJson(String, String, #[serde(default)] Option<Map<String, Value>>);
+
+pub fn decode_json(body: &[u8]) -> Result<Vec<LokiStream>, String> {
+    let request: PushRequestJson = serde_json::from_slice(body)
+        .map_err(|err| format!("Invalid Loki JSON request: {}", err))?;
+    request
+        .streams
+        .into_iter()
+        .map(|stream| {
+            let entries = stream
+                .values
+                .into_iter()
+                .map(|EntryJson(timestamp, line, metadata)| {
+                    let timestamp_ns = timestamp
+                        .parse()
+                        .map_err(|_| format!("Invalid timestamp: {}", timestamp))?;
+                    Ok(LokiEntry {
+                        timestamp_ns,
+                        line,
+                        metadata: metadata.unwrap_or_default(),
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(LokiStream {
+                labels: stream.stream,
+                entries,
+            })
+        })
+        .collect()
+}
+
+pub fn decode_protobuf(body: &[u8]) -> Result<Vec<LokiStream>, String> {
+    let body = snap::raw::Decoder::new()
+        .decompress_vec(body)
+        .map_err(|err| format!("Invalid snappy body: {}", err))?;
+    let request = PushRequest::decode(body.as_slice())
+        .map_err(|err| format!("Invalid Loki protobuf request: {}", err))?;
+    request
+        .streams
+        .into_iter()
+        .map(|stream| {
+            let entries = stream
+                .entries
+                .into_iter()
+                .map(|entry| {
+                    let timestamp_ns = match entry.timestamp {
+                        Some(timestamp) => timestamp
+                            .seconds
+                            .checked_mul(1_000_000_000)
+                            .and_then(|ns| ns.checked_add(timestamp.nanos as i64))
+                            .ok_or_else(|| format!("Invalid timestamp: {}s", timestamp.seconds))?,
+                        None => 0,
+                    };
+                    Ok(LokiEntry {
+                        timestamp_ns,
+                        line: entry.line,
+                        metadata: entry
+                            .structured_metadata
+                            .into_iter()
+                            .map(|pair| (pair.name, Value::from(pair.value)))
+                            .collect(),
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(LokiStream {
+                labels: parse_labels(&stream.labels)?,
+                entries,
+            })
+        })
+        .collect()
+}
+
+// Parses a Prometheus label set like {job="nginx", level="error"}
+fn parse_labels(labels: &str) -> Result<Map<String, Value>, String> {
+    let invalid = || format!("Invalid labels: {}", labels);
+    let mut chars = labels
+        .trim()
+        .strip_prefix('{')
+        .and_then(|labels| labels.strip_suffix('}'))
+        .ok_or_else(invalid)?
+        .chars();
+
+    let mut result = Map::new();
+    let mut name = String::new();
+    while let Some(char) = chars.next() {
+        match char {
+            '=' => {
+                if chars.next() != Some('"') {
+                    return Err(invalid());
+                }
+                let mut value = String::new();
+                loop {
+                    match chars.next().ok_or_else(invalid)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(invalid)? {
+                            'n' => value.push('\n'),
+                            char => value.push(char),
+                        },
+                        char => value.push(char),
+                    }
+                }
+                result.insert(name.trim().to_string(), Value::from(value));
+                name.clear();
+            }
+            ',' if name.trim().is_empty() => name.clear(),
+            char => name.push(char),
+        }
+    }
+    if !name.trim().is_empty() {
+        return Err(invalid());
+    }
+    Ok(result)
+}
+
+// Every stream belongs to the service of the token, an `app_id` label must match it
+pub fn to_logs(app_id: &str, streams: Vec<LokiStream>) -> Result<Vec<structs::Log>, String> {
+    let mut logs = Vec::new();
+    for stream in streams {
+        if let Some(label) = stream.labels.get("app_id").and_then(|label| label.as_str()) {
+            if label != app_id {
+                return Err(format!(
+                    "You specified a wrong app_id. You specified {} but your token contains {}",
+                    label, app_id
+                ));
+            }
+        }
+
+        let r#type = match stream.labels.get("type").and_then(|label| label.as_str()) {
+            Some(r#type) => Some(r#type.to_string()),
+            None => LEVEL_LABELS
+                .iter()
+                .find_map(|label| stream.labels.get(*label).and_then(|label| label.as_str()))
+                .and_then(get_severity_type),
+        };
+
+        for entry in stream.entries {
+            let mut fields = Map::new();
+            fields.insert("labels".to_string(), Value::Object(stream.labels.clone()));
+            if !entry.metadata.is_empty() {
+                fields.insert("metadata".to_string(), Value::Object(entry.metadata));
+            }
+
+            logs.push(structs::Log {
+                _id: None,
+                app_id: Some(app_id.to_string()),
+                type_: r#type.clone(),
+                message: entry.line,
+                timestamp: match entry.timestamp_ns {
+                    0 => None,
+                    timestamp_ns => Some(timestamp_ns / 1_000_000),
+                },
+                fields: Some(Value::Object(fields)),
+                trace_id: None,
+                span_id: None,
+                parent_span_id: None,
+            });
+        }
+    }
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loki::proto::{Entry, Stream, Timestamp};
+
+    fn push_body(seconds: i64, nanos: i32) -> Vec<u8> {
+        let request = PushRequest {
+            streams: vec![Stream {
+                labels: "{job=\"nginx\"}".to_string(),
+                entries: vec![Entry {
+                    timestamp: Some(Timestamp { seconds, nanos }),
+                    line: "hello".to_string(),
+                    structured_metadata: vec![],
+                }],
+                hash: 0,
+            }],
+        };
+        snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_protobuf_timestamps() {
+        let streams = decode_protobuf(&push_body(1_700_000_000, 5)).unwrap();
+        assert_eq!(
+            streams[0].entries[0].timestamp_ns,
+            1_700_000_000_000_000_005
+        );
+        assert_eq!(streams[0].labels["job"], "nginx");
+    }
+
+    #[test]
+    fn rejects_overflowing_protobuf_timestamps() {
+        assert!(decode_protobuf(&push_body(i64::MAX / 1_000, 0)).is_err());
+        assert!(decode_protobuf(&push_body(i64::MAX / 1_000_000_000, i32::MAX)).is_err());
+    }
+}
@@ -1,12 +1,18 @@
+mod auth;
 mod cleaner;
 mod config;
 mod cron;
 mod dbconfig;
 mod filesconfig;
+mod grpc;
 mod handlers;
+mod loki;
+mod otlp;
 mod response;
 mod route;
+mod sinks;
 mod structs;
+mod syslog;
 mod userconfig;
 mod utils;
 use crate::cron::cron::start_cron;
@@ -18,14 +24,24 @@ use config::Config;
 use dotenv::dotenv;
 use mongodb::{options::ClientOptions, Client};
 use route::create_router;
-use std::sync::Arc;
+use sinks::sink::SinkHandle;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use tower_http::cors::CorsLayer;
+use utils::logs_service_side::{
+    pipeline::PipelineCache, rate_limit::TokenBucket, redact::RedactionCache,
+};
 
 #[derive(Debug)]
 pub struct AppState {
     conf: Config,
     db: mongodb::Database,
+    rate_limits: Mutex<HashMap<String, TokenBucket>>,
+    pipelines: Mutex<PipelineCache>,
+    redaction_rules: Mutex<RedactionCache>,
+    sinks: Vec<SinkHandle>,
 }
 
 #[tokio::main]
@@ -78,6 +94,10 @@ async fn main() {
     let app_state = Arc::new(AppState {
         db: db.clone(),
         conf: config.clone(),
+        rate_limits: Mutex::new(HashMap::new()),
+        pipelines: Mutex::new(HashMap::new()),
+        redaction_rules: Mutex::new(HashMap::new()),
+        sinks: sinks::sink::start_sinks(&config.sinks),
     });
 
     let clean_result = cleaner::clean(app_state.clone()).await;
@@ -89,6 +109,16 @@ async fn main() {
 
     let app = create_router(app_state.clone()).layer(cors);
 
+    // Start gRPC server
+    if config.grpc.enabled {
+        tokio::spawn(grpc::server::start_grpc(app_state.clone()));
+    }
+
+    // Start syslog listeners
+    if config.syslog.enabled {
+        syslog::listener::start_syslog(app_state.clone()).await;
+    }
+
     // Start cron
     thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -97,7 +127,7 @@ async fn main() {
 
     println!("🚀 Server started successfully");
     axum::Server::bind(&"0.0.0.0:8000".parse().unwrap())
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
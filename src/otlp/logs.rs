@@ -0,0 +1,117 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    otlp::proto::{any_value, AnyValue, ExportLogsServiceRequest, KeyValue},
+    structs,
+    utils::logs_service_side::severity::get_severity_type,
+};
+
+// Same types as the syslog listener, from the severity number ranges of the OTLP data model
+fn get_type(severity_number: i32, severity_text: &str) -> Option<String> {
+    let r#type = match severity_number {
+        1..=8 => "debug",
+        9..=12 => "info",
+        13..=16 => "warning",
+        17..=20 => "error",
+        21..=24 => "critical",
+        _ => return get_severity_type(severity_text),
+    };
+    Some(r#type.to_string())
+}
+
+fn to_json(value: &AnyValue) -> Value {
+    match &value.value {
+        None => Value::Null,
+        Some(any_value::Value::StringValue(value)) => Value::from(value.as_str()),
+        Some(any_value::Value::BoolValue(value)) => Value::from(*value),
+        Some(any_value::Value::IntValue(value)) => Value::from(*value),
+        Some(any_value::Value::DoubleValue(value)) => Value::from(*value),
+        Some(any_value::Value::ArrayValue(array)) => {
+            Value::Array(array.values.iter().map(to_json).collect())
+        }
+        Some(any_value::Value::KvlistValue(list)) => Value::Object(to_fields(&list.values)),
+        Some(any_value::Value::BytesValue(value)) => Value::from(hex::encode(value)),
+    }
+}
+
+// Dots are replaced as MongoDB field names can't be queried with them (service.name -> service_name)
+fn to_fields(attributes: &[KeyValue]) -> Map<String, Value> {
+    attributes
+        .iter()
+        .map(|attribute| {
+            let value = attribute.value.as_ref().map(to_json).unwrap_or(Value::Null);
+            (attribute.key.replace('.', "_"), value)
+        })
+        .collect()
+}
+
+// Trace and span ids made of zeros are invalid and mean the log isn't part of a trace
+fn to_id(id: &[u8]) -> Option<String> {
+    if id.iter().all(|byte| *byte == 0) {
+        return None;
+    }
+    Some(hex::encode(id))
+}
+
+pub fn to_logs(app_id: &str, request: ExportLogsServiceRequest) -> Vec<structs::Log> {
+    let mut logs = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_logs
+            .resource
+            .map(|resource| to_fields(&resource.attributes))
+            .unwrap_or_default();
+
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_logs.scope.map(|scope| scope.name).unwrap_or_default();
+
+            for record in scope_logs.log_records {
+                let message = match record.body.as_ref().map(to_json) {
+                    Some(Value::String(message)) => message,
+                    Some(Value::Null) | None => record.event_name.clone(),
+                    Some(body) => body.to_string(),
+                };
+                let timestamp = match record.time_unix_nano {
+                    0 => record.observed_time_unix_nano,
+                    time_unix_nano => time_unix_nano,
+                };
+
+                let mut fields = Map::new();
+                if !resource.is_empty() {
+                    fields.insert("resource".to_string(), Value::Object(resource.clone()));
+                }
+                if !record.attributes.is_empty() {
+                    fields.insert(
+                        "attributes".to_string(),
+                        Value::Object(to_fields(&record.attributes)),
+                    );
+                }
+                if !scope.is_empty() {
+                    fields.insert("scope".to_string(), Value::from(scope.as_str()));
+                }
+                if !record.severity_text.is_empty() {
+                    fields.insert(
+                        "severity_text".to_string(),
+                        Value::from(record.severity_text.as_str()),
+                    );
+                }
+
+                logs.push(structs::Log {
+                    _id: None,
+                    app_id: Some(app_id.to_string()),
+                    type_: get_type(record.severity_number, &record.severity_text),
+                    message,
+                    timestamp: match timestamp {
+                        0 => None,
+                        timestamp => Some((timestamp / 1_000_000) as i64),
+                    },
+                    fields: Some(Value::Object(fields)),
+                    trace_id: to_id(&record.trace_id),
+                    span_id: to_id(&record.span_id),
+                    // Log records don't carry the parent of their span
+                    parent_span_id: None,
+                });
+            }
+        }
+    }
+    logs
+}
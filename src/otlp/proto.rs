@@ -0,0 +1,236 @@
+// Subset of the OpenTelemetry protocol (opentelemetry-proto v1) needed to receive logs.
+// Messages decode from protobuf with prost and from the OTLP JSON encoding with serde.
+#![allow(clippy::enum_variant_names)]
+
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportLogsServiceRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub resource_logs: Vec<ResourceLogs>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportLogsServiceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub partial_success: Option<ExportLogsPartialSuccess>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportLogsPartialSuccess {
+    #[prost(int64, tag = "1")]
+    pub rejected_log_records: i64,
+    #[prost(string, tag = "2")]
+    pub error_message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceLogs {
+    #[prost(message, optional, tag = "1")]
+    pub resource: Option<Resource>,
+    #[prost(message, repeated, tag = "2")]
+    pub scope_logs: Vec<ScopeLogs>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Resource {
+    #[prost(message, repeated, tag = "1")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "2")]
+    pub dropped_attributes_count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScopeLogs {
+    #[prost(message, optional, tag = "1")]
+    pub scope: Option<InstrumentationScope>,
+    #[prost(message, repeated, tag = "2")]
+    pub log_records: Vec<LogRecord>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InstrumentationScope {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+    #[prost(message, repeated, tag = "3")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "4")]
+    pub dropped_attributes_count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogRecord {
+    #[prost(fixed64, tag = "1")]
+    #[serde(deserialize_with = "number_or_string")]
+    pub time_unix_nano: u64,
+    #[prost(fixed64, tag = "11")]
+    #[serde(deserialize_with = "number_or_string")]
+    pub observed_time_unix_nano: u64,
+    #[prost(int32, tag = "2")]
+    pub severity_number: i32,
+    #[prost(string, tag = "3")]
+    pub severity_text: String,
+    #[prost(message, optional, tag = "5")]
+    pub body: Option<AnyValue>,
+    #[prost(message, repeated, tag = "6")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "7")]
+    pub dropped_attributes_count: u32,
+    #[prost(fixed32, tag = "8")]
+    pub flags: u32,
+    // Trace and span ids are hex encoded in JSON, unlike other bytes
+    #[prost(bytes = "vec", tag = "9")]
+    #[serde(deserialize_with = "hex_bytes")]
+    pub trace_id: Vec<u8>,
+    #[prost(bytes = "vec", tag = "10")]
+    #[serde(deserialize_with = "hex_bytes")]
+    pub span_id: Vec<u8>,
+    #[prost(string, tag = "12")]
+    pub event_name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(default)]
+pub struct KeyValue {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(message, optional, tag = "2")]
+    pub value: Option<AnyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(default)]
+pub struct ArrayValue {
+    #[prost(message, repeated, tag = "1")]
+    pub values: Vec<AnyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(default)]
+pub struct KeyValueList {
+    #[prost(message, repeated, tag = "1")]
+    pub values: Vec<KeyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Deserialize)]
+#[serde(from = "AnyValueJson")]
+pub struct AnyValue {
+    #[prost(oneof = "any_value::Value", tags = "1, 2, 3, 4, 5, 6, 7")]
+    pub value: Option<any_value::Value>,
+}
+
+pub mod any_value {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(string, tag = "1")]
+        StringValue(String),
+        #[prost(bool, tag = "2")]
+        BoolValue(bool),
+        #[prost(int64, tag = "3")]
+        IntValue(i64),
+        #[prost(double, tag = "4")]
+        DoubleValue(f64),
+        #[prost(message, tag = "5")]
+        ArrayValue(super::ArrayValue),
+        #[prost(message, tag = "6")]
+        KvlistValue(super::KeyValueList),
+        #[prost(bytes = "vec", tag = "7")]
+        BytesValue(Vec<u8>),
+    }
+}
+
+// In JSON the oneof is flattened into AnyValue, e.g. {"stringValue": "..."}
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnyValueJson {
+    string_value: Option<String>,
+    bool_value: Option<bool>,
+    #[serde(default, deserialize_with = "optional_number_or_string")]
+    int_value: Option<i64>,
+    double_value: Option<f64>,
+    array_value: Option<ArrayValue>,
+    kvlist_value: Option<KeyValueList>,
+    bytes_value: Option<String>,
+}
+
+impl From<AnyValueJson> for AnyValue {
+    fn from(json: AnyValueJson) -> Self {
+        use any_value::Value;
+        let value = if let Some(value) = json.string_value {
+            Some(Value::StringValue(value))
+        } else if let Some(value) = json.bool_value {
+            Some(Value::BoolValue(value))
+        } else if let Some(value) = json.int_value {
+            Some(Value::IntValue(value))
+        } else if let Some(value) = json.double_value {
+            Some(Value::DoubleValue(value))
+        } else if let Some(value) = json.array_value {
+            Some(Value::ArrayValue(value))
+        } else if let Some(value) = json.kvlist_value {
+            Some(Value::KvlistValue(value))
+        } else {
+            json.bytes_value.map(|value| {
+                Value::BytesValue(
+                    base64::engine::general_purpose::STANDARD
+                        .decode(value)
+                        .unwrap_or_default(),
+                )
+            })
+        };
+        AnyValue { value }
+    }
+}
+
+// 64 bits integers can be encoded as JSON numbers or strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(i128),
+    String(String),
+}
+
+fn number_or_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i128> + Default,
+{
+    Ok(optional_number_or_string(deserializer)?.unwrap_or_default())
+}
+
+fn optional_number_or_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i128>,
+{
+    let number = match Option::<NumberOrString>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(NumberOrString::Number(number)) => number,
+        Some(NumberOrString::String(string)) => string
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid integer: {}", string)))?,
+    };
+    T::try_from(number)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("integer out of range: {}", number)))
+}
+
+fn hex_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let string = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    hex::decode(string).map_err(serde::de::Error::custom)
+}
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use axum::{
+    middleware,
     routing::{delete, get, post},
     Router,
 };
 
-use crate::{handlers, AppState};
+use crate::{auth::legacy_token::extract_legacy_token, handlers, AppState};
 
 pub fn create_router(app_state: Arc<AppState>) -> Router {
     Router::new()
@@ -15,6 +16,52 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
         )
         // User
         .route("/login", post(handlers::user::login::login_handler))
+        .route("/logout", post(handlers::user::logout::logout_handler))
+        .route("/refresh", post(handlers::user::refresh::refresh_handler))
+        .route(
+            "/oidc/login",
+            get(handlers::user::oidc::oidc_login::oidc_login_handler),
+        )
+        .route(
+            "/oidc/callback",
+            get(handlers::user::oidc::oidc_callback::oidc_callback_handler),
+        )
+        .route(
+            "/login_2fa",
+            post(handlers::user::login_2fa::login_2fa_handler),
+        )
+        .route(
+            "/create_api_key",
+            post(handlers::user::api_key::create_api_key::create_api_key_handler),
+        )
+        .route(
+            "/get_api_keys",
+            post(handlers::user::api_key::get_api_keys::get_api_keys_handler),
+        )
+        .route(
+            "/revoke_api_key",
+            delete(handlers::user::api_key::revoke_api_key::revoke_api_key_handler),
+        )
+        .route(
+            "/enroll_totp",
+            post(handlers::user::totp::enroll_totp::enroll_totp_handler),
+        )
+        .route(
+            "/confirm_totp",
+            post(handlers::user::totp::confirm_totp::confirm_totp_handler),
+        )
+        .route(
+            "/disable_totp",
+            post(handlers::user::totp::disable_totp::disable_totp_handler),
+        )
+        .route(
+            "/logout_everywhere",
+            post(handlers::user::logout_everywhere::logout_everywhere_handler),
+        )
+        .route(
+            "/get_sessions",
+            post(handlers::user::get_sessions::get_sessions_handler),
+        )
         .route(
             "/check_auth_token",
             post(handlers::user::check_auth_token::check_auth_token_handler),
@@ -45,8 +92,24 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             delete(handlers::user::admin::delete_user::delete_user_handler),
         )
         .route(
-            "/set_user_permissions",
-            post(handlers::user::admin::set_user_permissions::set_user_permissions_handler),
+            "/set_user_roles",
+            post(handlers::user::admin::set_user_roles::set_user_roles_handler),
+        )
+        .route(
+            "/get_audit_log",
+            post(handlers::user::admin::audit::get_audit_log::get_audit_log_handler),
+        )
+        .route(
+            "/unlock_user",
+            post(handlers::user::admin::unlock_user::unlock_user_handler),
+        )
+        .route(
+            "/get_user_sessions",
+            post(handlers::user::admin::session::get_user_sessions::get_user_sessions_handler),
+        )
+        .route(
+            "/revoke_user_sessions",
+            delete(handlers::user::admin::session::revoke_user_sessions::revoke_user_sessions_handler),
         )
         .route(
             "/get_users",
@@ -64,6 +127,14 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             "/delete_service",
             delete(handlers::user::admin::delete_service::delete_service_handler),
         )
+        .route(
+            "/set_service_limits",
+            post(handlers::user::admin::set_service_limits::set_service_limits_handler),
+        )
+        .route(
+            "/get_services_usage",
+            post(handlers::user::admin::get_services_usage::get_services_usage_handler),
+        )
         .route(
             "/add_type",
             post(handlers::user::admin::add_type::add_type_handler),
@@ -92,6 +163,10 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             "/set_telegram_chat",
             post(handlers::user::admin::set_telegram_chat::set_telegram_chat_handler),
         )
+        .route(
+            "/set_require_admin_2fa",
+            post(handlers::user::admin::set_require_admin_2fa::set_require_admin_2fa_handler),
+        )
         .route(
             "/add_db",
             post(handlers::user::admin::db::add_db::add_db_handler),
@@ -128,12 +203,100 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             "/download_save",
             get(handlers::user::admin::db::download_save::download_save_handler),
         )
+        .route(
+            "/add_redaction_rule",
+            post(handlers::user::admin::redaction::add_redaction_rule::add_redaction_rule_handler),
+        )
+        .route(
+            "/get_redaction_rules",
+            post(handlers::user::admin::redaction::get_redaction_rules::get_redaction_rules_handler),
+        )
+        .route(
+            "/delete_redaction_rule",
+            delete(handlers::user::admin::redaction::delete_redaction_rule::delete_redaction_rule_handler),
+        )
+        .route(
+            "/test_redaction",
+            post(handlers::user::admin::redaction::test_redaction::test_redaction_handler),
+        )
+        .route(
+            "/set_pipeline",
+            post(handlers::user::admin::pipeline::set_pipeline::set_pipeline_handler),
+        )
+        .route(
+            "/get_pipeline",
+            post(handlers::user::admin::pipeline::get_pipeline::get_pipeline_handler),
+        )
+        .route(
+            "/delete_pipeline",
+            delete(handlers::user::admin::pipeline::delete_pipeline::delete_pipeline_handler),
+        )
+        .route(
+            "/test_pipeline",
+            post(handlers::user::admin::pipeline::test_pipeline::test_pipeline_handler),
+        )
+        .route(
+            "/add_sampling_rule",
+            post(handlers::user::admin::sampling::add_sampling_rule::add_sampling_rule_handler),
+        )
+        .route(
+            "/get_sampling_rules",
+            post(handlers::user::admin::sampling::get_sampling_rules::get_sampling_rules_handler),
+        )
+        .route(
+            "/delete_sampling_rule",
+            delete(handlers::user::admin::sampling::delete_sampling_rule::delete_sampling_rule_handler),
+        )
+        .route(
+            "/add_syslog_mapping",
+            post(handlers::user::admin::syslog::add_syslog_mapping::add_syslog_mapping_handler),
+        )
+        .route(
+            "/get_syslog_mappings",
+            post(handlers::user::admin::syslog::get_syslog_mappings::get_syslog_mappings_handler),
+        )
+        .route(
+            "/delete_syslog_mapping",
+            delete(handlers::user::admin::syslog::delete_syslog_mapping::delete_syslog_mapping_handler),
+        )
+        .route(
+            "/add_webhook",
+            post(handlers::user::admin::webhook::add_webhook::add_webhook_handler),
+        )
+        .route(
+            "/get_webhooks",
+            post(handlers::user::admin::webhook::get_webhooks::get_webhooks_handler),
+        )
+        .route(
+            "/delete_webhook",
+            delete(handlers::user::admin::webhook::delete_webhook::delete_webhook_handler),
+        )
+        .route(
+            "/get_sinks_stats",
+            post(handlers::user::admin::sinks::get_sinks_stats::get_sinks_stats_handler),
+        )
 
         // Logs user side
         .route(
             "/get_logs",
             post(handlers::logs_user_side::get_logs::get_logs_handler),
         )
+        .route(
+            "/get_issues",
+            post(handlers::logs_user_side::get_issues::get_issues_handler),
+        )
+        .route(
+            "/get_issue_logs",
+            post(handlers::logs_user_side::get_issue_logs::get_issue_logs_handler),
+        )
+        .route(
+            "/get_trace",
+            post(handlers::logs_user_side::get_trace::get_trace_handler),
+        )
+        .route(
+            "/get_ingestion_stats",
+            post(handlers::logs_user_side::get_ingestion_stats::get_ingestion_stats_handler),
+        )
         // Admin logs user side
         .route(
             "/regenerate_service_token",
@@ -143,6 +306,12 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             "/delete_log",
             delete(handlers::logs_user_side::admin::delete_log::delete_log_handler),
         )
+        .route(
+            "/resolve_issue",
+            post(handlers::logs_user_side::admin::resolve_issue::resolve_issue_handler),
+        )
+        // User routes accept the token of older clients in the body or the query string
+        .route_layer(middleware::from_fn(extract_legacy_token))
         // Logs service side
         .route(
             "/service/add_message",
@@ -152,5 +321,21 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             "/service/add_messages",
             post(handlers::logs_service_side::add_messages::add_messages_handler),
         )
+        .route(
+            "/v1/logs",
+            post(handlers::logs_service_side::otlp_logs::otlp_logs_handler),
+        )
+        .route(
+            "/loki/api/v1/push",
+            post(handlers::logs_service_side::loki_push::loki_push_handler),
+        )
+        .route(
+            "/webhooks/:webhook_id",
+            post(handlers::logs_service_side::webhook::webhook_handler),
+        )
+        .route(
+            "/webhooks/:webhook_id/:secret",
+            post(handlers::logs_service_side::webhook::webhook_handler),
+        )
         .with_state(app_state)
 }
@@ -0,0 +1,239 @@
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::SinkOutput;
+
+pub struct BatchResult {
+    // Logs the destination refused for good, they are not retried
+    pub rejected: usize,
+    // Logs which failed because of a transient error
+    pub retry: Vec<Value>,
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    fn success() -> Self {
+        BatchResult {
+            rejected: 0,
+            retry: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn retry(batch: Vec<Value>, error: String) -> Self {
+        BatchResult {
+            rejected: 0,
+            retry: batch,
+            error: Some(error),
+        }
+    }
+}
+
+pub async fn send_batch(
+    client: &reqwest::Client,
+    output: &SinkOutput,
+    batch: Vec<Value>,
+) -> BatchResult {
+    match output {
+        SinkOutput::Http { url, headers } => {
+            let mut request = client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .body(Value::from(batch.clone()).to_string());
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            match request.send().await {
+                Ok(res) if res.status().is_success() => BatchResult::success(),
+                // Client errors other than rate limiting won't succeed on the next try
+                Ok(res) if res.status().is_client_error() && res.status().as_u16() != 429 => {
+                    BatchResult {
+                        rejected: batch.len(),
+                        retry: Vec::new(),
+                        error: Some(format!("{} responded with {}", url, res.status())),
+                    }
+                }
+                Ok(res) => {
+                    BatchResult::retry(batch, format!("{} responded with {}", url, res.status()))
+                }
+                Err(err) => BatchResult::retry(batch, err.to_string()),
+            }
+        }
+        SinkOutput::Elasticsearch {
+            url,
+            index,
+            headers,
+        } => send_bulk(client, url, index, headers, batch).await,
+        SinkOutput::File { path } => {
+            let mut lines = String::new();
+            for log in &batch {
+                lines.push_str(&log.to_string());
+                lines.push('\n');
+            }
+            let file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await;
+            let res = match file {
+                Ok(mut file) => file.write_all(lines.as_bytes()).await,
+                Err(err) => Err(err),
+            };
+            match res {
+                Ok(()) => BatchResult::success(),
+                Err(err) => BatchResult::retry(batch, format!("{}: {}", path, err)),
+            }
+        }
+    }
+}
+
+// Documents are indexed with the id of the log so retrying a batch never duplicates them
+async fn send_bulk(
+    client: &reqwest::Client,
+    url: &str,
+    index: &str,
+    headers: &std::collections::HashMap<String, String>,
+    batch: Vec<Value>,
+) -> BatchResult {
+    let mut body = String::new();
+    for log in &batch {
+        let mut document = log.clone();
+        let id = document
+            .as_object_mut()
+            .and_then(|document| document.remove("_id"));
+        body.push_str(&json!({ "index": { "_index": index, "_id": id } }).to_string());
+        body.push('\n');
+        body.push_str(&document.to_string());
+        body.push('\n');
+    }
+
+    let bulk_url = format!("{}/_bulk", url.trim_end_matches('/'));
+    let mut request = client
+        .post(&bulk_url)
+        .header("Content-Type", "application/x-ndjson")
+        .body(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let res = match request.send().await {
+        Ok(res) => res,
+        Err(err) => return BatchResult::retry(batch, err.to_string()),
+    };
+    if !res.status().is_success() {
+        return BatchResult::retry(
+            batch,
+            format!("{} responded with {}", bulk_url, res.status()),
+        );
+    }
+    let res: Value = match res.bytes().await {
+        Ok(res) => serde_json::from_slice(&res).unwrap_or_default(),
+        Err(err) => return BatchResult::retry(batch, err.to_string()),
+    };
+    if res.get("errors") != Some(&Value::Bool(true)) {
+        return BatchResult::success();
+    }
+
+    // Items are in the order of the request, only overloaded shards are worth a retry
+    let items = res
+        .get("items")
+        .and_then(|items| items.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let mut result = BatchResult::success();
+    for (log, item) in batch.into_iter().zip(items) {
+        let item = item.get("index").unwrap_or(&item);
+        let status = item
+            .get("status")
+            .and_then(|status| status.as_u64())
+            .unwrap_or(0);
+        if item.get("error").is_none() {
+            continue;
+        }
+        result.error = Some(item["error"].to_string());
+        if status == 429 || status >= 500 {
+            result.retry.push(log);
+        } else {
+            result.rejected += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use watchtower_test_support::StubServer;
+
+    fn elasticsearch(server: &StubServer) -> SinkOutput {
+        SinkOutput::Elasticsearch {
+            url: format!("{}/", server.url),
+            index: "logs".to_string(),
+            headers: HashMap::new(),
+        }
+    }
+
+    fn logs() -> Vec<Value> {
+        (1..=3)
+            .map(|id| json!({ "_id": id.to_string(), "message": format!("log {}", id) }))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn indexes_logs_with_their_id() {
+        let server = StubServer::start().await;
+        server.respond(200, r#"{"errors":false,"items":[]}"#);
+
+        let result = send_batch(&reqwest::Client::new(), &elasticsearch(&server), logs()).await;
+        assert_eq!(result.rejected, 0);
+        assert!(result.retry.is_empty());
+
+        let (path, body) = server.requests().remove(0);
+        assert_eq!(path, "/_bulk");
+        let lines: Vec<Value> = String::from_utf8(body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[0],
+            json!({ "index": { "_index": "logs", "_id": "1" } })
+        );
+        assert_eq!(lines[1], json!({ "message": "log 1" }));
+    }
+
+    #[tokio::test]
+    async fn retries_only_overloaded_items_of_a_partial_bulk() {
+        let server = StubServer::start().await;
+        server.respond(
+            200,
+            &json!({
+                "errors": true,
+                "items": [
+                    { "index": { "_id": "1", "status": 201 } },
+                    { "index": { "_id": "2", "status": 429, "error": { "type": "es_rejected_execution_exception" } } },
+                    { "index": { "_id": "3", "status": 400, "error": { "type": "mapper_parsing_exception" } } },
+                ]
+            })
+            .to_string(),
+        );
+
+        let result = send_batch(&reqwest::Client::new(), &elasticsearch(&server), logs()).await;
+        assert_eq!(result.rejected, 1);
+        // Retried logs keep their id so the next bulk doesn't duplicate them
+        assert_eq!(result.retry, vec![logs()[1].clone()]);
+        assert!(result.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn retries_the_whole_batch_on_error_status() {
+        let server = StubServer::start().await;
+        server.respond(503, "unavailable");
+
+        let result = send_batch(&reqwest::Client::new(), &elasticsearch(&server), logs()).await;
+        assert_eq!(result.rejected, 0);
+        assert_eq!(result.retry, logs());
+    }
+}
@@ -0,0 +1,279 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::{sync::mpsc, time::Instant};
+
+use crate::{
+    config::{Sink, SinkOutput},
+    sinks::output::send_batch,
+    structs, AppState,
+};
+
+// Delay before the first retry of a batch, doubled on each attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+pub struct SinkStats {
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+    retries: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+#[derive(Debug)]
+pub struct SinkHandle {
+    config: Sink,
+    sender: mpsc::Sender<Value>,
+    stats: Arc<SinkStats>,
+}
+
+impl SinkHandle {
+    fn accepts(&self, app_id: &str, r#type: &str) -> bool {
+        (self.config.app_ids.is_empty() || self.config.app_ids.iter().any(|id| id == app_id))
+            && (self.config.types.is_empty() || self.config.types.iter().any(|t| t == r#type))
+    }
+
+    pub fn get_stats(&self) -> structs::SinkStats {
+        let kind = match self.config.output {
+            SinkOutput::Http { .. } => "http",
+            SinkOutput::Elasticsearch { .. } => "elasticsearch",
+            SinkOutput::File { .. } => "file",
+        };
+        structs::SinkStats {
+            name: self.config.name.clone(),
+            kind: kind.to_string(),
+            queued: self.sender.max_capacity() - self.sender.capacity(),
+            queue_size: self.sender.max_capacity(),
+            forwarded: self.stats.forwarded.load(Ordering::Relaxed),
+            dropped: self.stats.dropped.load(Ordering::Relaxed),
+            failed: self.stats.failed.load(Ordering::Relaxed),
+            retries: self.stats.retries.load(Ordering::Relaxed),
+            last_error: self.stats.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+pub fn start_sinks(sinks: &[Sink]) -> Vec<SinkHandle> {
+    sinks
+        .iter()
+        .map(|config| {
+            let (sender, receiver) = mpsc::channel(config.queue_size.max(1));
+            let stats = Arc::new(SinkStats::default());
+            tokio::spawn(run_sink(config.clone(), receiver, stats.clone()));
+            println!("📤 Forwarding logs to sink {}", config.name);
+            SinkHandle {
+                config: config.clone(),
+                sender,
+                stats,
+            }
+        })
+        .collect()
+}
+
+// Never waits on a sink: when its queue is full the log is dropped for it and counted
+pub fn forward_log(app_state: &AppState, app_id: &str, r#type: &str, log: Value) {
+    for sink in app_state
+        .sinks
+        .iter()
+        .filter(|sink| sink.accepts(app_id, r#type))
+    {
+        if sink.sender.try_send(log.clone()).is_err() {
+            sink.stats.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn run_sink(config: Sink, mut receiver: mpsc::Receiver<Value>, stats: Arc<SinkStats>) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap();
+    let batch_size = config.batch_size.max(1);
+    let flush_interval = Duration::from_millis(config.flush_interval_ms);
+
+    while let Some(log) = receiver.recv().await {
+        // Wait for a full batch, or send what was received once the flush interval is over
+        let mut batch = vec![log];
+        let deadline = Instant::now() + flush_interval;
+        while batch.len() < batch_size {
+            match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(log)) => batch.push(log),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        send_with_retries(&client, &config, &stats, batch).await;
+    }
+}
+
+async fn send_with_retries(
+    client: &reqwest::Client,
+    config: &Sink,
+    stats: &SinkStats,
+    mut batch: Vec<Value>,
+) {
+    let mut attempt = 0;
+    loop {
+        let len = batch.len();
+        let result = send_batch(client, &config.output, batch).await;
+        let forwarded = len - result.rejected - result.retry.len();
+        stats
+            .forwarded
+            .fetch_add(forwarded as u64, Ordering::Relaxed);
+        stats
+            .failed
+            .fetch_add(result.rejected as u64, Ordering::Relaxed);
+        if let Some(error) = result.error {
+            println!(
+                "❌ Failed to forward logs to sink {}: {}",
+                config.name, error
+            );
+            *stats.last_error.lock().unwrap() = Some(error);
+        }
+
+        batch = result.retry;
+        if batch.is_empty() {
+            return;
+        }
+        if attempt >= config.max_retries {
+            stats
+                .failed
+                .fetch_add(batch.len() as u64, Ordering::Relaxed);
+            return;
+        }
+
+        stats.retries.fetch_add(1, Ordering::Relaxed);
+        let delay = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt));
+        tokio::time::sleep(delay.min(RETRY_MAX_DELAY)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use watchtower_test_support::StubServer;
+
+    fn http_sink(server: &StubServer, batch_size: usize, max_retries: u32) -> Sink {
+        Sink {
+            name: "stub".to_string(),
+            output: SinkOutput::Http {
+                url: format!("{}/logs", server.url),
+                headers: HashMap::new(),
+            },
+            app_ids: vec!["app".to_string()],
+            types: Vec::new(),
+            batch_size,
+            flush_interval_ms: 100,
+            queue_size: 10,
+            max_retries,
+        }
+    }
+
+    // Waits for the stub to receive `count` requests
+    async fn wait_for_requests(server: &StubServer, count: usize) -> Vec<Value> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.requests().len() < count && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        server
+            .requests()
+            .into_iter()
+            .map(|(path, body)| {
+                assert_eq!(path, "/logs");
+                serde_json::from_slice(&body).unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sends_full_batches_then_the_rest_after_the_interval() {
+        let server = StubServer::start().await;
+        let sinks = start_sinks(&[http_sink(&server, 2, 0)]);
+        for id in 1..=3 {
+            sinks[0].sender.send(json!({ "id": id })).await.unwrap();
+        }
+
+        let requests = wait_for_requests(&server, 2).await;
+        assert_eq!(
+            requests,
+            vec![json!([{ "id": 1 }, { "id": 2 }]), json!([{ "id": 3 }])]
+        );
+        let stats = sinks[0].get_stats();
+        assert_eq!((stats.forwarded, stats.failed), (3, 0));
+    }
+
+    #[tokio::test]
+    async fn only_accepts_matching_logs() {
+        let server = StubServer::start().await;
+        let mut config = http_sink(&server, 1, 0);
+        config.types = vec!["error".to_string()];
+        let sinks = start_sinks(&[config]);
+
+        assert!(sinks[0].accepts("app", "error"));
+        assert!(!sinks[0].accepts("app", "info"));
+        assert!(!sinks[0].accepts("other", "error"));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let server = StubServer::start().await;
+        let config = http_sink(&server, 10, 2);
+        let stats = SinkStats::default();
+        server.respond(503, "unavailable");
+
+        let batch = vec![json!({ "id": 1 }), json!({ "id": 2 })];
+        send_with_retries(&reqwest::Client::new(), &config, &stats, batch).await;
+
+        assert_eq!(wait_for_requests(&server, 2).await.len(), 2);
+        assert_eq!(stats.forwarded.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.retries.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.failed.load(Ordering::Relaxed), 0);
+        assert!(stats.last_error.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = StubServer::start().await;
+        let config = http_sink(&server, 10, 1);
+        let stats = SinkStats::default();
+        server.respond(500, "error");
+        server.respond(429, "slow down");
+
+        let batch = vec![json!({ "id": 1 }), json!({ "id": 2 })];
+        send_with_retries(&reqwest::Client::new(), &config, &stats, batch).await;
+
+        assert_eq!(wait_for_requests(&server, 2).await.len(), 2);
+        assert_eq!(stats.forwarded.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.failed.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let server = StubServer::start().await;
+        let config = http_sink(&server, 10, 3);
+        let stats = SinkStats::default();
+        server.respond(400, "bad request");
+
+        send_with_retries(
+            &reqwest::Client::new(),
+            &config,
+            &stats,
+            vec![json!({ "id": 1 })],
+        )
+        .await;
+
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(stats.retries.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.failed.load(Ordering::Relaxed), 1);
+    }
+}
@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 pub struct JwtUserClaims {
     pub exp: usize,
     pub user_id: String,
+    // Id of the session, tokens stop working once it is revoked
+    #[serde(default)]
+    pub jti: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -13,11 +16,6 @@ pub struct JwtServiceClaims {
     pub timestamp: i64,
 }
 
-#[derive(Deserialize)]
-pub struct AuthTokenJSON {
-    pub token: String,
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Log {
     pub _id: Option<String>,
@@ -25,12 +23,76 @@ pub struct Log {
     pub type_: Option<String>,
     pub message: String,
     pub timestamp: Option<i64>,
+    pub fields: Option<serde_json::Value>,
+    pub trace_id: Option<String>,
+    pub span_id: Option<String>,
+    pub parent_span_id: Option<String>,
+}
+
+// Logs of a trace grouped by span, children are sorted by their first log
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TraceSpan {
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub app_ids: Vec<String>,
+    pub start: i64,
+    pub end: i64,
+    pub logs: Vec<String>,
+    pub children: Vec<TraceSpan>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Session {
+    pub _id: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub user_agent: Option<String>,
+    // Session of the token used for the request
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiKey {
+    pub _id: String,
+    pub name: String,
+    // Start of the key, the key itself is only shown on creation
+    pub prefix: String,
+    pub scopes: Vec<RoleGrant>,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Service {
     pub _id: Option<String>,
     pub app_name: Option<String>,
+    pub group: Option<String>,
+}
+
+// Each role includes the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleScope {
+    Global,
+    Service,
+    Group,
+}
+
+// `target` is the app_id of a service scope or the name of a group scope
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RoleGrant {
+    pub role: Role,
+    pub scope: RoleScope,
+    pub target: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -39,6 +101,7 @@ pub struct User {
     pub username: String,
     pub password: Option<String>,
     pub permissions: Vec<String>,
+    pub roles: Vec<RoleGrant>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -50,6 +113,117 @@ pub struct Type {
     pub importance: i32,
     pub notifications: Vec<String>,
     pub parents: Vec<String>,
+    pub notify_new_issues_only: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Issue {
+    pub _id: Option<String>,
+    pub fingerprint: String,
+    pub app_id: String,
+    pub type_: String,
+    pub message: String,
+    pub last_message: String,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub count: i64,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditActor {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub api_key_id: Option<String>,
+}
+
+// `changes` holds { field: { before, after } } for every modified field
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub _id: String,
+    pub timestamp: i64,
+    pub actor: AuditActor,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub changes: serde_json::Value,
+    pub ip: Option<String>,
+    pub forwarded_for: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RedactionRule {
+    pub _id: Option<String>,
+    pub name: String,
+    pub app_id: Option<String>,
+    pub preset: Option<String>,
+    pub pattern: Option<String>,
+    pub replacement: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SamplingRule {
+    pub _id: Option<String>,
+    pub app_id: Option<String>,
+    pub type_: Option<String>,
+    pub rate: f64,
+    pub drop: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IngestionStats {
+    pub app_id: String,
+    pub day: String,
+    pub sampled: i64,
+    pub dropped: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServiceUsage {
+    pub app_id: String,
+    pub app_name: String,
+    pub rate_limit_per_second: f64,
+    pub rate_limit_burst: f64,
+    pub daily_quota: i64,
+    pub available_tokens: Option<f64>,
+    pub day: String,
+    pub received: i64,
+    pub rejected: i64,
+    pub sampled: i64,
+    pub dropped: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyslogMapping {
+    pub _id: Option<String>,
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub app_id: String,
+    pub severity_types: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Webhook {
+    pub _id: Option<String>,
+    pub app_id: String,
+    pub name: String,
+    pub verification: String,
+    pub signature_header: Option<String>,
+    pub secret: String,
+    pub mapping: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SinkStats {
+    pub name: String,
+    pub kind: String,
+    pub queued: usize,
+    pub queue_size: usize,
+    pub forwarded: u64,
+    pub dropped: u64,
+    pub failed: u64,
+    pub retries: u64,
+    pub last_error: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -63,6 +237,7 @@ pub struct Database {
     pub message: String,
     pub custom_name: String,
     pub authentication_database: String,
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -0,0 +1,261 @@
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, BufReader},
+    net::{TcpListener, TcpStream, UdpSocket},
+};
+
+use crate::{
+    structs,
+    syslog::parser::{parse_syslog, SyslogMessage},
+    utils::logs_service_side::{add_message::add_message, rate_limit::check_ingestion_limits},
+    AppState,
+};
+
+// Types used when a mapping doesn't define its own, indexed by severity
+const DEFAULT_SEVERITY_TYPES: [&str; 8] = [
+    "critical", "critical", "critical", "error", "warning", "info", "info", "debug",
+];
+
+// Messages bigger than this are rejected, over TCP the connection is closed
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+// Enough for any length up to MAX_MESSAGE_SIZE
+const MAX_LENGTH_DIGITS: u64 = 6;
+
+pub async fn start_syslog(app_state: Arc<AppState>) {
+    let config = app_state.conf.syslog.clone();
+
+    if let Some(udp_address) = config.udp_address {
+        let socket = UdpSocket::bind(&udp_address)
+            .await
+            .expect("Failed to bind syslog UDP socket");
+        println!("📨 Syslog listening on udp://{}", udp_address);
+        tokio::spawn(listen_udp(app_state.clone(), socket));
+    }
+
+    if let Some(tcp_address) = config.tcp_address {
+        let listener = TcpListener::bind(&tcp_address)
+            .await
+            .expect("Failed to bind syslog TCP socket");
+        println!("📨 Syslog listening on tcp://{}", tcp_address);
+        tokio::spawn(listen_tcp(app_state.clone(), listener));
+    }
+}
+
+async fn listen_udp(app_state: Arc<AppState>, socket: UdpSocket) {
+    let mut buffer = vec![0; MAX_MESSAGE_SIZE];
+    loop {
+        let Ok((size, _)) = socket.recv_from(&mut buffer).await else {
+            continue;
+        };
+        let raw = String::from_utf8_lossy(&buffer[..size]).to_string();
+        tokio::spawn(handle_syslog_message(app_state.clone(), raw));
+    }
+}
+
+async fn listen_tcp(app_state: Arc<AppState>, listener: TcpListener) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        tokio::spawn(handle_tcp_connection(app_state.clone(), stream));
+    }
+}
+
+async fn handle_tcp_connection(app_state: Arc<AppState>, stream: TcpStream) {
+    let mut reader = BufReader::new(stream);
+    while let Some(raw) = read_frame(&mut reader).await {
+        let raw = String::from_utf8_lossy(&raw).to_string();
+        handle_syslog_message(app_state.clone(), raw).await;
+    }
+}
+
+// Supports both octet counting ("LEN MSG") and newline delimited framing (RFC 6587).
+// Returns None when the connection must be closed.
+async fn read_frame<R: AsyncBufRead + Unpin>(reader: &mut R) -> Option<Vec<u8>> {
+    let buffer = match reader.fill_buf().await {
+        Ok(buffer) if !buffer.is_empty() => buffer,
+        _ => return None,
+    };
+
+    if buffer[0].is_ascii_digit() {
+        // The length and its separator, a longer prefix can't be valid
+        let mut length = Vec::new();
+        let mut prefix = (&mut *reader).take(MAX_LENGTH_DIGITS + 1);
+        if prefix.read_until(b' ', &mut length).await.is_err() || length.last() != Some(&b' ') {
+            return None;
+        }
+        let length = String::from_utf8_lossy(&length)
+            .trim()
+            .parse::<usize>()
+            .ok()?;
+        if length > MAX_MESSAGE_SIZE {
+            println!(
+                "❌ Syslog message too big ({} bytes), closing connection",
+                length
+            );
+            return None;
+        }
+        let mut message = vec![0; length];
+        reader.read_exact(&mut message).await.ok()?;
+        Some(message)
+    } else {
+        let mut message = Vec::new();
+        let mut frame = (&mut *reader).take(MAX_MESSAGE_SIZE as u64);
+        match frame.read_until(b'\n', &mut message).await {
+            Ok(0) | Err(_) => None,
+            // The limit was reached before the end of the message
+            Ok(size) if size == MAX_MESSAGE_SIZE && message.last() != Some(&b'\n') => {
+                println!("❌ Syslog message too big, closing connection");
+                None
+            }
+            Ok(_) => Some(message),
+        }
+    }
+}
+
+async fn handle_syslog_message(app_state: Arc<AppState>, raw: String) {
+    let Some(message) = parse_syslog(&raw) else {
+        println!("❌ Invalid syslog message: {}", raw.trim_end());
+        return;
+    };
+
+    let Some(mapping) = find_mapping(&app_state, &message).await else {
+        // Messages from unknown sources are ignored
+        return;
+    };
+
+    let app_id = mapping.get_str("app_id").unwrap().to_string();
+    let r#type = mapping
+        .get_array("severity_types")
+        .ok()
+        .and_then(|types| types.get(message.severity as usize))
+        .and_then(|type_| type_.as_str())
+        .unwrap_or(DEFAULT_SEVERITY_TYPES[message.severity as usize])
+        .to_string();
+
+    if check_ingestion_limits(app_state.clone(), &app_id, 1)
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut fields = serde_json::Map::new();
+    fields.insert("facility".to_string(), message.facility.into());
+    fields.insert("severity".to_string(), message.severity.into());
+    for (key, value) in [
+        ("hostname", message.hostname),
+        ("app_name", message.app_name),
+        ("proc_id", message.proc_id),
+        ("msg_id", message.msg_id),
+        ("structured_data", message.structured_data),
+    ] {
+        if let Some(value) = value {
+            fields.insert(key.to_string(), value.into());
+        }
+    }
+
+    let res = add_message(
+        app_state.clone(),
+        structs::Log {
+            _id: None,
+            app_id: Some(app_id),
+            type_: Some(r#type),
+            message: message.message,
+            timestamp: message.timestamp,
+            fields: Some(serde_json::Value::Object(fields)),
+            trace_id: None,
+            span_id: None,
+            parent_span_id: None,
+        },
+    )
+    .await;
+    if let Err((_, err)) = res {
+        println!("❌ Failed to add syslog message: {}", err);
+    }
+}
+
+// The most specific mapping wins: hostname and app name, then app name, then hostname
+async fn find_mapping(app_state: &AppState, message: &SyslogMessage) -> Option<Document> {
+    let collection: mongodb::Collection<Document> = app_state.db.collection("syslog_mappings");
+    let filter = doc! {
+        "hostname": { "$in": [null, message.hostname.clone()] },
+        "app_name": { "$in": [null, message.app_name.clone()] },
+    };
+    let mut cursor = collection.find(filter, None).await.unwrap();
+
+    let mut mapping: Option<(u8, Document)> = None;
+    while let Some(candidate) = cursor.try_next().await.unwrap() {
+        let specificity = match (
+            candidate.get_str("hostname").is_ok(),
+            candidate.get_str("app_name").is_ok(),
+        ) {
+            (true, true) => 3,
+            (false, true) => 2,
+            (true, false) => 1,
+            (false, false) => 0,
+        };
+        if mapping.as_ref().is_none_or(|(best, _)| specificity > *best) {
+            mapping = Some((specificity, candidate));
+        }
+    }
+    mapping.map(|(_, mapping)| mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_frames(input: &[u8]) -> Vec<Vec<u8>> {
+        let mut reader = BufReader::new(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = read_frame(&mut reader).await {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[tokio::test]
+    async fn reads_octet_counted_frames() {
+        let frames = read_frames(b"5 <13>a3 <1>").await;
+        assert_eq!(frames, vec![b"<13>a".to_vec(), b"<1>".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn reads_newline_delimited_frames() {
+        let frames = read_frames(b"<13>first\n<13>second\n<13>last").await;
+        assert_eq!(
+            frames,
+            vec![
+                b"<13>first\n".to_vec(),
+                b"<13>second\n".to_vec(),
+                b"<13>last".to_vec()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn closes_on_long_length_prefix() {
+        let input = vec![b'1'; 1024 * 1024];
+        assert!(read_frames(&input).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn closes_on_length_above_limit() {
+        let input = format!("{} <13>", MAX_MESSAGE_SIZE + 1);
+        assert!(read_frames(input.as_bytes()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn closes_on_newline_frame_above_limit() {
+        let mut input = b"<13>ok\n".to_vec();
+        input.extend(vec![b'a'; MAX_MESSAGE_SIZE * 4]);
+        input.push(b'\n');
+        let frames = read_frames(&input).await;
+        assert_eq!(frames, vec![b"<13>ok\n".to_vec()]);
+    }
+}
@@ -0,0 +1,217 @@
+use chrono::{Datelike, NaiveDateTime, TimeZone};
+
+#[derive(Debug, Default)]
+pub struct SyslogMessage {
+    pub facility: u8,
+    pub severity: u8,
+    pub timestamp: Option<i64>,
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub proc_id: Option<String>,
+    pub msg_id: Option<String>,
+    pub structured_data: Option<String>,
+    pub message: String,
+}
+
+// Parses RFC 5424 messages and falls back to RFC 3164 (BSD) ones
+pub fn parse_syslog(raw: &str) -> Option<SyslogMessage> {
+    let raw = raw.trim_end_matches(['\r', '\n', '\0']);
+    let rest = raw.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    let pri: u8 = rest[..end].parse().ok()?;
+    if pri > 191 {
+        return None;
+    }
+    let rest = &rest[end + 1..];
+
+    let mut message = SyslogMessage {
+        facility: pri / 8,
+        severity: pri % 8,
+        ..Default::default()
+    };
+
+    match rest.strip_prefix("1 ") {
+        Some(rest) => parse_rfc5424(rest, &mut message),
+        None => parse_rfc3164(rest, &mut message),
+    }
+    Some(message)
+}
+
+fn nil(value: &str) -> Option<String> {
+    if value == "-" || value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+// TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]
+fn parse_rfc5424(rest: &str, message: &mut SyslogMessage) {
+    let mut parts = rest.splitn(6, ' ');
+    message.timestamp = parts
+        .next()
+        .and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(timestamp).ok())
+        .map(|timestamp| timestamp.timestamp_millis());
+    message.hostname = parts.next().and_then(nil);
+    message.app_name = parts.next().and_then(nil);
+    message.proc_id = parts.next().and_then(nil);
+    message.msg_id = parts.next().and_then(nil);
+
+    let rest = parts.next().unwrap_or_default();
+    let (structured_data, msg) = split_structured_data(rest);
+    message.structured_data = nil(structured_data);
+    message.message = msg.trim_start_matches('\u{feff}').to_string();
+}
+
+// Structured data is either "-" or a list of [id key="value"...] elements
+fn split_structured_data(rest: &str) -> (&str, &str) {
+    if !rest.starts_with('[') {
+        return match rest.split_once(' ') {
+            Some((structured_data, msg)) => (structured_data, msg),
+            None => (rest, ""),
+        };
+    }
+
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut depth = 0;
+    for (index, char) in rest.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match char {
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => depth += 1,
+            ']' if !in_quotes => depth -= 1,
+            ' ' if !in_quotes && depth == 0 => {
+                return (&rest[..index], &rest[index + 1..]);
+            }
+            _ => {}
+        }
+    }
+    (rest, "")
+}
+
+// Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG
+fn parse_rfc3164(rest: &str, message: &mut SyslogMessage) {
+    let mut rest = rest;
+
+    // The timestamp has no year nor timezone, assume the current year in UTC
+    if let (Some(date), Some(after)) = (rest.get(..15), rest.get(16..)) {
+        let now = chrono::Utc::now();
+        let date = format!("{} {}", now.year(), date);
+        if let Ok(date) = NaiveDateTime::parse_from_str(&date, "%Y %b %e %H:%M:%S") {
+            message.timestamp = Some(chrono::Utc.from_utc_datetime(&date).timestamp_millis());
+            rest = after;
+
+            if let Some((hostname, after)) = rest.split_once(' ') {
+                message.hostname = nil(hostname);
+                rest = after;
+            }
+        }
+    }
+
+    // The tag ends at the first character that isn't alphanumeric (usually `[` or `:`)
+    let tag_end = rest
+        .find(|char: char| !(char.is_alphanumeric() || "-_./".contains(char)))
+        .unwrap_or(rest.len());
+    if tag_end > 0 && rest[tag_end..].starts_with(['[', ':']) {
+        message.app_name = Some(rest[..tag_end].to_string());
+        rest = &rest[tag_end..];
+        if let Some(after) = rest.strip_prefix('[') {
+            if let Some((proc_id, after)) = after.split_once(']') {
+                message.proc_id = nil(proc_id);
+                rest = after;
+            }
+        }
+        rest = rest.strip_prefix(':').unwrap_or(rest);
+    }
+
+    message.message = rest.trim_start().to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc5424() {
+        let message = parse_syslog(
+            "<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut=\"3\" eventSource=\"Application\"] \u{feff}An application event\n",
+        )
+        .unwrap();
+        assert_eq!(message.facility, 20);
+        assert_eq!(message.severity, 5);
+        assert_eq!(message.timestamp, Some(1065910455003));
+        assert_eq!(message.hostname.as_deref(), Some("mymachine.example.com"));
+        assert_eq!(message.app_name.as_deref(), Some("evntslog"));
+        assert_eq!(message.proc_id, None);
+        assert_eq!(message.msg_id.as_deref(), Some("ID47"));
+        assert_eq!(
+            message.structured_data.as_deref(),
+            Some("[exampleSDID@32473 iut=\"3\" eventSource=\"Application\"]")
+        );
+        assert_eq!(message.message, "An application event");
+    }
+
+    #[test]
+    fn parses_rfc5424_without_structured_data_nor_message() {
+        let message = parse_syslog("<34>1 - host app 123 - -").unwrap();
+        assert_eq!(message.timestamp, None);
+        assert_eq!(message.proc_id.as_deref(), Some("123"));
+        assert_eq!(message.structured_data, None);
+        assert_eq!(message.message, "");
+    }
+
+    #[test]
+    fn keeps_brackets_quoted_in_structured_data() {
+        let message = parse_syslog("<14>1 - host app - - [id key=\"a ] b\"][other] hello").unwrap();
+        assert_eq!(
+            message.structured_data.as_deref(),
+            Some("[id key=\"a ] b\"][other]")
+        );
+        assert_eq!(message.message, "hello");
+    }
+
+    #[test]
+    fn parses_rfc3164() {
+        let message =
+            parse_syslog("<34>Oct 11 22:14:15 mymachine su[42]: 'su root' failed on /dev/pts/8")
+                .unwrap();
+        assert_eq!(message.facility, 4);
+        assert_eq!(message.severity, 2);
+        assert!(message.timestamp.is_some());
+        assert_eq!(message.hostname.as_deref(), Some("mymachine"));
+        assert_eq!(message.app_name.as_deref(), Some("su"));
+        assert_eq!(message.proc_id.as_deref(), Some("42"));
+        assert_eq!(message.message, "'su root' failed on /dev/pts/8");
+    }
+
+    #[test]
+    fn parses_rfc3164_without_timestamp() {
+        let message = parse_syslog("<13>kernel: something happened").unwrap();
+        assert_eq!(message.timestamp, None);
+        assert_eq!(message.hostname, None);
+        assert_eq!(message.app_name.as_deref(), Some("kernel"));
+        assert_eq!(message.message, "something happened");
+    }
+
+    #[test]
+    fn multibyte_character_after_rfc3164_date_does_not_panic() {
+        let message = parse_syslog("<13>Oct 11 22:14:15é host app: message").unwrap();
+        assert_eq!(message.timestamp, None);
+
+        let message = parse_syslog("<13>Oct 11 22:1é").unwrap();
+        assert_eq!(message.timestamp, None);
+    }
+
+    #[test]
+    fn rejects_invalid_priority() {
+        assert!(parse_syslog("no priority").is_none());
+        assert!(parse_syslog("<192>1 - - - - - -").is_none());
+        assert!(parse_syslog("<abc>message").is_none());
+        assert!(parse_syslog("<13message").is_none());
+    }
+}
@@ -1,10 +1,12 @@
-use argon2::{
-    password_hash::{PasswordHasher, SaltString},
-    Argon2,
-};
+use argon2::password_hash::{PasswordHasher, SaltString};
 use mongodb::bson::{doc, Document};
+use rand_core::OsRng;
 
 use crate::config::Config;
+use crate::utils::{
+    get_argon2::get_argon2,
+    verify_password::{verify_password, PasswordCheck},
+};
 
 pub async fn config(config: Config, db: mongodb::Database) -> bool {
     let users_collections = db.collection("users");
@@ -14,22 +16,37 @@ pub async fn config(config: Config, db: mongodb::Database) -> bool {
         .expect("Failed to get user");
 
     let config = config.clone();
-    let root_user_password = config.security.root_user_password;
+    let root_user_password = config.security.root_user_password.clone();
     // hash password
-    let salt = SaltString::encode_b64(&config.security.password_salt.as_bytes()).unwrap();
-    let argon2 = Argon2::default();
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = get_argon2(&config);
     let password_hash = argon2
         .hash_password(root_user_password.as_bytes(), &salt)
         .unwrap()
         .to_string();
     if user.is_none() {
         println!("🔧 Creating root user");
-        let user = doc! { "username": "root", "password": password_hash, "permissions": ["administrator"] };
+        let user = doc! {
+            "username": "root",
+            "password": password_hash,
+            "permissions": ["administrator"],
+            "roles": [{ "role": "admin", "scope": "global", "target": null }],
+        };
         users_collections.insert_one(user, None).await.unwrap();
         println!("👤 Created root user");
     } else {
         let auto_update_root_user = config.security.auto_update_root_user;
-        if auto_update_root_user {
+        // The salt is random, so only rewrite the hash when the password or the parameters changed
+        let root_user: Option<Document> = users_collections
+            .find_one(doc! { "username": "root" }, None)
+            .await
+            .expect("Failed to get root user");
+        let check = root_user
+            .as_ref()
+            .and_then(|root_user| root_user.get_str("password").ok())
+            .map(|hash| verify_password(&config, &root_user_password, hash))
+            .unwrap_or(PasswordCheck::Invalid);
+        if auto_update_root_user && check != PasswordCheck::Valid {
             users_collections
                 .update_one(
                     doc! { "username": "root" },
@@ -41,5 +58,21 @@ pub async fn config(config: Config, db: mongodb::Database) -> bool {
             println!("🔧 Updated root user password");
         }
     }
+
+    // Administrators from before roles become global admins
+    let migrated = users_collections
+        .update_many(
+            doc! { "permissions": "administrator", "roles": { "$exists": false } },
+            doc! { "$set": { "roles": [{ "role": "admin", "scope": "global", "target": null }] } },
+            None,
+        )
+        .await
+        .unwrap();
+    if migrated.modified_count > 0 {
+        println!(
+            "🔧 Migrated {} administrators to the global admin role",
+            migrated.modified_count
+        );
+    }
     return true;
 }
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::{auth::extractors::AuthUser, AppState};
+
+// Values of these fields are replaced, only the fact that they changed is kept
+const SENSITIVE_FIELDS: [&str; 8] = [
+    "password",
+    "authorization",
+    "token",
+    "secret",
+    "hash",
+    "connection_string",
+    "webhook",
+    "recovery_codes",
+];
+
+// Applied to nested documents and arrays too, e.g. a sink output holding a token
+fn redact(key: &str, value: Option<&Bson>) -> Bson {
+    let key = key.to_lowercase();
+    match value {
+        None => Bson::Null,
+        Some(_) if SENSITIVE_FIELDS.iter().any(|field| key.contains(field)) => {
+            Bson::String("[redacted]".to_string())
+        }
+        Some(Bson::Document(document)) => Bson::Document(
+            document
+                .iter()
+                .map(|(key, value)| (key.clone(), redact(key, Some(value))))
+                .collect(),
+        ),
+        Some(Bson::Array(values)) => Bson::Array(
+            values
+                .iter()
+                .map(|value| redact(&key, Some(value)))
+                .collect(),
+        ),
+        Some(value) => value.clone(),
+    }
+}
+
+// Top level fields that differ, as { field: { before, after } }
+fn get_changes(before: Option<&Document>, after: Option<&Document>) -> Document {
+    let empty = Document::new();
+    let before = before.unwrap_or(&empty);
+    let after = after.unwrap_or(&empty);
+
+    let mut changes = Document::new();
+    for key in before.keys().chain(after.keys()) {
+        if key == "_id" || changes.contains_key(key) || before.get(key) == after.get(key) {
+            continue;
+        }
+        changes.insert(
+            key,
+            doc! {
+                "before": redact(key, before.get(key)),
+                "after": redact(key, after.get(key)),
+            },
+        );
+    }
+    changes
+}
+
+// Records an administrative action, entries are never updated nor deleted through the API.
+// `before` and `after` are the state of the target around the action, when it has one.
+pub async fn audit_log(
+    app_state: &Arc<AppState>,
+    actor: &AuthUser,
+    action: &str,
+    target_type: &str,
+    target_id: Option<&str>,
+    before: Option<&Document>,
+    after: Option<&Document>,
+) {
+    let db = &app_state.db;
+    let username = match mongodb::bson::oid::ObjectId::parse_str(&actor.user_id) {
+        Ok(object_id) => db
+            .collection::<Document>("users")
+            .find_one(doc! { "_id": object_id }, None)
+            .await
+            .unwrap()
+            .and_then(|user| user.get_str("username").ok().map(String::from)),
+        Err(_) => None,
+    };
+
+    let entry = doc! {
+        "timestamp": chrono::Utc::now().timestamp_millis(),
+        "actor": {
+            "user_id": &actor.user_id,
+            "username": username,
+            "api_key_id": &actor.api_key_id,
+        },
+        "action": action,
+        "target_type": target_type,
+        "target_id": target_id,
+        "changes": get_changes(before, after),
+        "ip": &actor.ip,
+        "forwarded_for": &actor.forwarded_for,
+    };
+
+    let collection: mongodb::Collection<Document> = db.collection("audit_log");
+    if let Err(err) = collection.insert_one(entry, None).await {
+        println!("❌ Failed to write audit log entry for {}: {}", action, err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_nested_sensitive_fields() {
+        let before = doc! {
+            "name": "elastic",
+            "output": { "url": "http://a", "headers": { "Authorization": "Bearer a" }, "token": "a" },
+            "hooks": [{ "webhook": "http://a" }],
+        };
+        let after = doc! {
+            "name": "elastic",
+            "output": { "url": "http://b", "headers": { "Authorization": "Bearer a" }, "token": "b" },
+            "hooks": [{ "webhook": "http://b" }],
+        };
+
+        assert_eq!(
+            get_changes(Some(&before), Some(&after)),
+            doc! {
+                "output": {
+                    "before": { "url": "http://a", "headers": { "Authorization": "[redacted]" }, "token": "[redacted]" },
+                    "after": { "url": "http://b", "headers": { "Authorization": "[redacted]" }, "token": "[redacted]" },
+                },
+                "hooks": {
+                    "before": [{ "webhook": "[redacted]" }],
+                    "after": [{ "webhook": "[redacted]" }],
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn redacts_whole_sensitive_documents() {
+        let after = doc! { "secret": { "value": "a" }, "name": "b" };
+        assert_eq!(
+            get_changes(None, Some(&after)),
+            doc! {
+                "secret": { "before": null, "after": "[redacted]" },
+                "name": { "before": null, "after": "b" },
+            }
+        );
+    }
+}
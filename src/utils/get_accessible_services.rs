@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    auth::extractors::AuthUser,
+    structs::{Role, RoleGrant, RoleScope},
+    utils::get_user_roles::get_user_roles,
+    AppState,
+};
+
+async fn grants_services(
+    app_state: &Arc<AppState>,
+    grants: &[RoleGrant],
+    role: Role,
+) -> Option<Vec<String>> {
+    let grants: Vec<_> = grants.iter().filter(|grant| grant.role >= role).collect();
+    if grants.iter().any(|grant| grant.scope == RoleScope::Global) {
+        return None;
+    }
+
+    let mut app_ids: Vec<String> = grants
+        .iter()
+        .filter(|grant| grant.scope == RoleScope::Service)
+        .filter_map(|grant| grant.target.clone())
+        .collect();
+    let groups: Vec<String> = grants
+        .iter()
+        .filter(|grant| grant.scope == RoleScope::Group)
+        .filter_map(|grant| grant.target.clone())
+        .collect();
+    if !groups.is_empty() {
+        let db = &app_state.db;
+        let collection: mongodb::Collection<Document> = db.collection("services");
+        let mut cursor = collection
+            .find(doc! { "group": { "$in": groups } }, None)
+            .await
+            .unwrap();
+        while cursor.advance().await.unwrap() {
+            let _id = cursor.current().get_object_id("_id").unwrap().to_hex();
+            if !app_ids.contains(&_id) {
+                app_ids.push(_id);
+            }
+        }
+    }
+    Some(app_ids)
+}
+
+// None when the user has the role globally, else the app_ids of the services they can access.
+// With an API key only the services allowed by both its owner and its scopes are returned.
+pub async fn get_accessible_services(
+    app_state: Arc<AppState>,
+    user: &AuthUser,
+    role: Role,
+) -> Option<Vec<String>> {
+    let grants = get_user_roles(app_state.clone(), &user.user_id).await;
+    let owner_app_ids = grants_services(&app_state, &grants, role).await;
+    let Some(scopes) = &user.scopes else {
+        return owner_app_ids;
+    };
+    let key_app_ids = grants_services(&app_state, scopes, role).await;
+    match (owner_app_ids, key_app_ids) {
+        (None, app_ids) | (app_ids, None) => app_ids,
+        (Some(owner_app_ids), Some(key_app_ids)) => Some(
+            owner_app_ids
+                .into_iter()
+                .filter(|app_id| key_app_ids.contains(app_id))
+                .collect(),
+        ),
+    }
+}
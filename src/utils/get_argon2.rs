@@ -0,0 +1,15 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+
+use crate::config::Config;
+
+pub fn get_argon2(config: &Config) -> Argon2<'static> {
+    let params = &config.security.argon2;
+    let params = Params::new(
+        params.memory_cost,
+        params.time_cost,
+        params.parallelism,
+        None,
+    )
+    .expect("Invalid security.argon2 parameters");
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
@@ -19,6 +19,7 @@ pub fn get_token_data(app_state: Arc<AppState>, jwt_token: String) -> JwtUserCla
     let error_object = JwtUserClaims {
         exp: 0,
         user_id: "".to_string(),
+        jti: "".to_string(),
     };
 
     if token_data.is_err() {
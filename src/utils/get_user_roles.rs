@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, Document};
+
+use crate::{structs::RoleGrant, AppState};
+
+pub async fn get_user_roles(app_state: Arc<AppState>, user_id: &str) -> Vec<RoleGrant> {
+    let Ok(object_id) = mongodb::bson::oid::ObjectId::parse_str(user_id) else {
+        return Vec::new();
+    };
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    let Some(user) = user else {
+        return Vec::new();
+    };
+
+    user.get_array("roles")
+        .map(|roles| {
+            roles
+                .iter()
+                .filter_map(|role| mongodb::bson::from_bson(role.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use crate::{
+    auth::extractors::AuthUser, structs::Role,
+    utils::get_accessible_services::get_accessible_services, AppState,
+};
+
+// Requested services the user can view, all of them when none were requested
+pub async fn get_viewable_apps(
+    app_state: Arc<AppState>,
+    user: &AuthUser,
+    target_apps: Option<Vec<String>>,
+) -> Option<Vec<String>> {
+    let accessible = get_accessible_services(app_state, user, Role::Viewer).await;
+    match (accessible, target_apps) {
+        (None, target_apps) => target_apps,
+        (Some(accessible), None) => Some(accessible),
+        (Some(accessible), Some(target_apps)) => Some(
+            target_apps
+                .into_iter()
+                .filter(|app_id| accessible.contains(app_id))
+                .collect(),
+        ),
+    }
+}
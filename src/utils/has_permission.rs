@@ -2,9 +2,21 @@ use std::sync::Arc;
 
 use mongodb::bson::doc;
 
-use crate::AppState;
+use crate::{
+    structs::{Role, RoleScope},
+    utils::get_user_roles::get_user_roles,
+    AppState,
+};
 
+// The administrator permission is the global admin role, API key scopes are not considered
 pub async fn has_permission(user_id: String, permission: String, app_state: Arc<AppState>) -> bool {
+    if permission == "administrator" {
+        let grants = get_user_roles(app_state, &user_id).await;
+        return grants
+            .iter()
+            .any(|grant| grant.role == Role::Admin && grant.scope == RoleScope::Global);
+    }
+
     let db = &app_state.db;
     let object_id = mongodb::bson::oid::ObjectId::parse_str(&user_id).unwrap();
     let user: Option<mongodb::bson::Document> = db
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, Document};
+
+use crate::{
+    auth::extractors::AuthUser,
+    structs::{Role, RoleGrant, RoleScope},
+    utils::get_user_roles::get_user_roles,
+    AppState,
+};
+
+async fn grants_allow(
+    app_state: &Arc<AppState>,
+    grants: &[RoleGrant],
+    role: Role,
+    app_id: Option<&str>,
+) -> bool {
+    let grants: Vec<_> = grants.iter().filter(|grant| grant.role >= role).collect();
+    if grants.iter().any(|grant| grant.scope == RoleScope::Global) {
+        return true;
+    }
+    let Some(app_id) = app_id else {
+        return false;
+    };
+    if grants
+        .iter()
+        .any(|grant| grant.scope == RoleScope::Service && grant.target.as_deref() == Some(app_id))
+    {
+        return true;
+    }
+    if !grants.iter().any(|grant| grant.scope == RoleScope::Group) {
+        return false;
+    }
+
+    let Ok(object_id) = mongodb::bson::oid::ObjectId::parse_str(app_id) else {
+        return false;
+    };
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("services");
+    let service = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    let group = service.and_then(|service| service.get_str("group").ok().map(String::from));
+    group.is_some_and(|group| {
+        grants.iter().any(|grant| {
+            grant.scope == RoleScope::Group && grant.target.as_deref() == Some(group.as_str())
+        })
+    })
+}
+
+// Without an app_id the role is needed globally, else on the service or its group.
+// With an API key both its owner and the scopes of the key need the role.
+pub async fn has_role(
+    app_state: Arc<AppState>,
+    user: &AuthUser,
+    role: Role,
+    app_id: Option<&str>,
+) -> bool {
+    let grants = get_user_roles(app_state.clone(), &user.user_id).await;
+    if !grants_allow(&app_state, &grants, role, app_id).await {
+        return false;
+    }
+    match &user.scopes {
+        Some(scopes) => grants_allow(&app_state, scopes, role, app_id).await,
+        None => true,
+    }
+}
@@ -1,19 +1,16 @@
 use std::sync::Arc;
 
-use argon2::{
-    password_hash::{PasswordHasher, SaltString},
-    Argon2,
-};
+use argon2::password_hash::{PasswordHasher, SaltString};
+use rand_core::OsRng;
 
-use crate::AppState;
+use crate::{utils::get_argon2::get_argon2, AppState};
 
+// PHC string with a random salt, e.g. $argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>
 pub fn hash_password(app_state: Arc<AppState>, password: String) -> String {
-    let config = app_state.conf.clone();
-    let salt = SaltString::encode_b64(&config.security.password_salt.as_bytes()).unwrap();
-    let argon2 = Argon2::default();
-    let password_hash = argon2
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = get_argon2(&app_state.conf);
+    argon2
         .hash_password(password.as_bytes(), &salt)
         .unwrap()
-        .to_string();
-    return password_hash;
+        .to_string()
 }
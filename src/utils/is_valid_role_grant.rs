@@ -0,0 +1,23 @@
+use mongodb::bson::{doc, Document};
+
+use crate::structs::{RoleGrant, RoleScope};
+
+// Service scopes need an existing service, group scopes a group name
+pub async fn is_valid_role_grant(db: &mongodb::Database, grant: &RoleGrant) -> bool {
+    match (grant.scope, grant.target.as_deref()) {
+        (RoleScope::Global, None) => true,
+        (RoleScope::Group, Some(group)) => !group.is_empty(),
+        (RoleScope::Service, Some(app_id)) => {
+            let Ok(object_id) = mongodb::bson::oid::ObjectId::parse_str(app_id) else {
+                return false;
+            };
+            let services: mongodb::Collection<Document> = db.collection("services");
+            services
+                .find_one(doc! { "_id": object_id }, None)
+                .await
+                .unwrap()
+                .is_some()
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{structs, AppState};
+
+use super::{
+    add_message::add_message, get_token_app_id::get_token_app_id,
+    rate_limit::check_ingestion_limits,
+};
+
+// Checks the token, the app_id and the ingestion limits before adding a batch of logs.
+// The app_id of the logs is set from the token, `app_id` is only checked against it.
+pub async fn add_logs(
+    app_state: Arc<AppState>,
+    token: String,
+    app_id: Option<String>,
+    logs: Vec<structs::Log>,
+) -> Result<usize, Response> {
+    let token_app_id = get_token_app_id(app_state.clone(), token).await?;
+
+    if let Some(app_id) = app_id.filter(|app_id| *app_id != token_app_id) {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            format!(
+                "You specified a wrong app_id. You specified {} but your token contains {}",
+                app_id, token_app_id
+            ),
+        )
+            .into_response());
+    }
+
+    check_ingestion_limits(app_state.clone(), &token_app_id, logs.len()).await?;
+
+    let logs_len = logs.len();
+    for log in logs {
+        let log = structs::Log {
+            app_id: Some(token_app_id.clone()),
+            ..log
+        };
+        if let Err(err) = add_message(app_state.clone(), log).await {
+            return Err(err.into_response());
+        }
+    }
+
+    Ok(logs_len)
+}
@@ -3,21 +3,60 @@ use std::sync::Arc;
 use axum::Json;
 use mongodb::bson::{doc, Document};
 use reqwest::StatusCode;
-use std::fs::File;
 
-use crate::AppState;
+use crate::{
+    sinks::sink::forward_log,
+    structs,
+    utils::send_notification::{send_discord_notification, send_telegram_notification},
+    AppState,
+};
+
+use super::{
+    fingerprint::compute_fingerprint,
+    ingestion_stats::increment_ingestion_stat,
+    pipeline::{get_compiled_pipeline, run_pipeline},
+    redact::{get_redaction_rules, redact, redact_value},
+    sampling::{get_sampling_decision, SamplingDecision},
+    track_issue::{track_issue, IssueOccurrence},
+};
 
 pub async fn add_message(
     app_state: Arc<AppState>,
-    _id: Option<String>,
-    app_id: Option<String>,
-    mut r#type: Option<String>,
-    message: String,
-    mut timestamp: Option<i64>,
+    log: structs::Log,
 ) -> Result<Json<serde_json::Value>, (StatusCode, &'static str)> {
     let db = &app_state.db;
     let collection: mongodb::Collection<Document> = db.collection("logs");
 
+    let app_id = log.app_id;
+    let trace_id = log.trace_id;
+    let span_id = log.span_id;
+    let parent_span_id = log.parent_span_id;
+    let mut r#type = log.type_;
+    let mut message = log.message;
+    let mut timestamp = log.timestamp;
+    let mut fields = match log.fields {
+        Some(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+
+    // Extract structured fields from the raw message
+    if let Some(steps) =
+        get_compiled_pipeline(&app_state, app_id.as_deref().unwrap_or_default()).await
+    {
+        let output = run_pipeline(&steps, r#type, message, timestamp, fields);
+        if output.dropped {
+            increment_ingestion_stat(db, app_id.as_deref().unwrap(), "dropped", 1).await;
+            return Ok(Json(serde_json::json!({
+                "status": "success",
+                "dropped": true,
+            })));
+        }
+        r#type = output.r#type;
+        message = output.message;
+        timestamp = output.timestamp;
+        fields = output.fields;
+    }
+
     if timestamp.is_none() {
         let current_date = chrono::Utc::now();
         let current_timestamp = current_date.timestamp_millis();
@@ -28,19 +67,90 @@ pub async fn add_message(
         r#type = Some("default".to_string());
     }
 
-    let res = collection
-        .insert_one(
+    let colllection: mongodb::Collection<Document> = db.collection("types");
+    let type_ = colllection
+        .find_one(
             doc! {
-                "app_id": app_id.clone(),
-                "timestamp": timestamp.clone(),
-                "type_": r#type.clone(),
-                "message": message.clone(),
+                "name": r#type.clone().unwrap()
             },
             None,
         )
         .await
         .unwrap();
 
+    // Sampling and drop rules never apply to important types
+    let importance = type_
+        .as_ref()
+        .and_then(|type_| type_.get_i32("importance").ok())
+        .unwrap_or(0);
+    if importance <= app_state.conf.ingestion.max_sampled_importance {
+        let decision =
+            get_sampling_decision(db, app_id.as_deref().unwrap(), r#type.as_deref().unwrap()).await;
+        if decision != SamplingDecision::Keep {
+            let field = if decision == SamplingDecision::Dropped {
+                "dropped"
+            } else {
+                "sampled"
+            };
+            increment_ingestion_stat(db, app_id.as_deref().unwrap(), field, 1).await;
+            return Ok(Json(serde_json::json!({
+                "status": "success",
+                field: true,
+            })));
+        }
+    }
+
+    // Secrets must never reach the database nor the notifications
+    let redaction_rules = get_redaction_rules(&app_state, app_id.as_deref()).await;
+    message = redact(&message, &redaction_rules).0;
+    let mut fields = serde_json::Value::Object(fields);
+    redact_value(&mut fields, &redaction_rules);
+
+    let fingerprint = compute_fingerprint(
+        app_id.as_deref().unwrap_or_default(),
+        r#type.as_deref().unwrap(),
+        &message,
+    );
+
+    let mut log = doc! {
+        "app_id": app_id.clone(),
+        "timestamp": timestamp.clone(),
+        "type_": r#type.clone(),
+        "message": message.clone(),
+        "fingerprint": fingerprint.clone(),
+    };
+    if fields.as_object().is_some_and(|fields| !fields.is_empty()) {
+        log.insert("fields", mongodb::bson::to_bson(&fields).unwrap());
+    }
+    if let Some(trace_id) = &trace_id {
+        log.insert("trace_id", trace_id);
+    }
+    if let Some(span_id) = &span_id {
+        log.insert("span_id", span_id);
+    }
+    if let Some(parent_span_id) = &parent_span_id {
+        log.insert("parent_span_id", parent_span_id);
+    }
+
+    let res = collection.insert_one(log, None).await.unwrap();
+
+    forward_log(
+        &app_state,
+        app_id.as_deref().unwrap_or_default(),
+        r#type.as_deref().unwrap(),
+        serde_json::json!({
+            "_id": res.inserted_id.as_object_id().unwrap().to_hex(),
+            "app_id": app_id.clone(),
+            "type": r#type.clone(),
+            "message": message.clone(),
+            "timestamp": timestamp,
+            "fields": fields,
+            "trace_id": trace_id,
+            "span_id": span_id,
+            "parent_span_id": parent_span_id,
+        }),
+    );
+
     let collection: mongodb::Collection<Document> = db.collection("services");
     let parsed_app_id = mongodb::bson::oid::ObjectId::parse_str(&app_id.clone().unwrap()).unwrap();
     let service = collection
@@ -59,74 +169,56 @@ pub async fn add_message(
 
     let service = service.unwrap();
 
-    let colllection: mongodb::Collection<Document> = db.collection("types");
-    let type_ = colllection
-        .find_one(
-            doc! {
-                "name": r#type.clone().unwrap()
-            },
-            None,
-        )
-        .await
-        .unwrap();
+    let occurrence = track_issue(
+        db,
+        &fingerprint,
+        app_id.as_deref().unwrap(),
+        r#type.as_deref().unwrap(),
+        &message,
+        timestamp.unwrap(),
+    )
+    .await;
+
     let conf = app_state.conf.clone();
     if !type_.is_none() {
         let type_ = type_.unwrap();
-        let notifications = type_.get("notifications").unwrap().as_array().unwrap();
+        let mut notifications = type_
+            .get("notifications")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+
+        // Only notify the first occurrence of an issue or its regressions
+        let notify_new_issues_only = type_.get_bool("notify_new_issues_only").unwrap_or(false);
+        if notify_new_issues_only && occurrence == IssueOccurrence::Known {
+            notifications.clear();
+        }
 
         if notifications.contains(&"discord".to_string().into()) {
-            let config_file = File::open("config.json").unwrap();
-            let config_json: serde_json::Value = serde_json::from_reader(config_file).unwrap();
-            let discord_webhook = config_json["discord_webhook"].as_str();
-            if !discord_webhook.is_none() {
-                let discord_webhook = discord_webhook.unwrap();
-                let message = format!(
-                    "<t:{}> __{}__\n**{}**\n{}\n➡️ [open](https://watch-t.vercel.app/dashboard?page=logs&services={}#log_{})",
-                    timestamp.unwrap(),
-                    service.get("app_name").unwrap().as_str().unwrap(),
-                    r#type.clone().unwrap(),
-                    message,
-                    app_id.unwrap(),
-                    res.inserted_id.as_object_id().unwrap().to_hex()
-                );
-
-                let client = reqwest::Client::new();
-                client
-                    .post(discord_webhook)
-                    .form(&serde_json::json!({ "content": message }))
-                    .send()
-                    .await
-                    .unwrap();
+            let mut message = format!(
+                "<t:{}> __{}__\n**{}**\n{}\n➡️ [open](https://watch-t.vercel.app/dashboard?page=logs&services={}#log_{})",
+                timestamp.unwrap(),
+                service.get("app_name").unwrap().as_str().unwrap(),
+                r#type.clone().unwrap(),
+                message,
+                app_id.clone().unwrap(),
+                res.inserted_id.as_object_id().unwrap().to_hex()
+            );
+            // Other logs of the request can be found with /get_trace
+            if let Some(trace_id) = &trace_id {
+                message.push_str(&format!("\n🧵 trace `{}`", trace_id));
             }
+            send_discord_notification(message).await;
         }
         if notifications.contains(&"telegram".to_string().into()) {
-            let config_file = File::open("config.json").unwrap();
-            let config_json: serde_json::Value = serde_json::from_reader(config_file).unwrap();
-            let telegram_chat = config_json["telegram_chat"].as_str();
-            if !telegram_chat.is_none() {
-                let telegram_chat = telegram_chat.unwrap();
-                let message = format!(
-                    "<b>{}</b>\n<i>{}</i>\n\n{}",
-                    service.get("app_name").unwrap().as_str().unwrap(),
-                    r#type.unwrap(),
-                    message
-                );
-
-                let client = reqwest::Client::new();
-                client
-                    .post(format!(
-                        "https://api.telegram.org/bot{}/sendMessage",
-                        conf.connections.telegram_token
-                    ))
-                    .form(&serde_json::json!({
-                        "chat_id": telegram_chat,
-                        "text": message,
-                        "parse_mode": "HTML",
-                    }))
-                    .send()
-                    .await
-                    .unwrap();
-            }
+            let message = format!(
+                "<b>{}</b>\n<i>{}</i>\n\n{}",
+                service.get("app_name").unwrap().as_str().unwrap(),
+                r#type.unwrap(),
+                message
+            );
+            send_telegram_notification(&conf, message).await;
         }
     }
 
@@ -0,0 +1,46 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+struct Normalizer {
+    uuid: Regex,
+    hex: Regex,
+    long_hex: Regex,
+    number: Regex,
+}
+
+fn normalizer() -> &'static Normalizer {
+    static NORMALIZER: OnceLock<Normalizer> = OnceLock::new();
+    NORMALIZER.get_or_init(|| Normalizer {
+        uuid: Regex::new(
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+        )
+        .unwrap(),
+        // Starknet / Ethereum addresses, hashes and any other 0x prefixed value
+        hex: Regex::new(r"\b0x[0-9a-fA-F]+\b").unwrap(),
+        long_hex: Regex::new(r"\b[0-9a-fA-F]{16,}\b").unwrap(),
+        number: Regex::new(r"\d+(\.\d+)?").unwrap(),
+    })
+}
+
+// Replace the variable parts of a message (ids, addresses, numbers) so that
+// repeated occurrences of the same event end up with the same text
+pub fn normalize_message(message: &str) -> String {
+    let normalizer = normalizer();
+    let message = normalizer.uuid.replace_all(message, "<uuid>");
+    let message = normalizer.hex.replace_all(&message, "<hex>");
+    let message = normalizer.long_hex.replace_all(&message, "<hex>");
+    let message = normalizer.number.replace_all(&message, "<num>");
+    message.trim().to_string()
+}
+
+pub fn compute_fingerprint(app_id: &str, type_: &str, message: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(app_id.as_bytes());
+    hasher.update([0]);
+    hasher.update(type_.as_bytes());
+    hasher.update([0]);
+    hasher.update(normalize_message(message).as_bytes());
+    hex::encode(hasher.finalize())
+}
@@ -0,0 +1,11 @@
+use axum::http::{header, HeaderMap};
+
+// Collectors (OTLP exporters, Promtail...) send the service token as `Authorization: Bearer <token>`
+pub fn get_bearer_token(headers: &HeaderMap) -> String {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .to_string()
+}
@@ -0,0 +1,34 @@
+use std::pin::Pin;
+
+use async_compression::tokio::bufread::GzipDecoder;
+use axum::{
+    extract::BodyStream,
+    http::{header, HeaderMap},
+};
+use futures::TryStreamExt;
+use tokio::io::{AsyncBufRead, AsyncReadExt, BufReader};
+use tokio_util::io::StreamReader;
+
+// Requests bodies are limited to 2 MB unless configured otherwise
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 2 * 1024 * 1024;
+
+// Streams the body, decompressing it on the fly. At most `max_body_size + 1` bytes are read so
+// the caller can tell a body that is too large (once decompressed) from one that fits.
+pub fn get_body_reader(
+    headers: &HeaderMap,
+    body: BodyStream,
+    max_body_size: u64,
+) -> Result<Pin<Box<dyn AsyncBufRead + Send>>, String> {
+    let body = StreamReader::new(body.map_err(std::io::Error::other));
+    let encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("identity");
+    match encoding {
+        "identity" => Ok(Box::pin(body.take(max_body_size + 1))),
+        "gzip" => Ok(Box::pin(BufReader::new(
+            GzipDecoder::new(body).take(max_body_size + 1),
+        ))),
+        encoding => Err(format!("Unsupported content encoding: {}", encoding)),
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::AppState;
+
+use super::{
+    check_service_token::check_service_token, get_service_token_data::get_service_token_data,
+};
+
+// Returns the service of a valid token, or the response to send when it isn't
+pub async fn get_token_app_id(app_state: Arc<AppState>, token: String) -> Result<String, Response> {
+    let valid = check_service_token(app_state.clone(), token.clone()).await;
+    let token_data = get_service_token_data(app_state, token);
+    match token_data.filter(|_| valid) {
+        Some(token_data) => Ok(token_data.app_id),
+        None => Err((StatusCode::UNAUTHORIZED, "Invalid token or token expired").into_response()),
+    }
+}
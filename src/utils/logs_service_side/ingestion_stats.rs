@@ -0,0 +1,26 @@
+use mongodb::{
+    bson::{doc, Document},
+    options::UpdateOptions,
+    Collection, Database,
+};
+
+// Stats are bucketed per UTC day
+pub fn get_day(timestamp_millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_millis)
+        .unwrap_or_default()
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+pub async fn increment_ingestion_stat(db: &Database, app_id: &str, field: &str, amount: i64) {
+    let collection: Collection<Document> = db.collection("ingestion_stats");
+    let day = get_day(chrono::Utc::now().timestamp_millis());
+    collection
+        .update_one(
+            doc! { "app_id": app_id, "day": day },
+            doc! { "$inc": { field: amount } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .unwrap();
+}
@@ -1,3 +1,16 @@
+pub mod add_logs;
 pub mod add_message;
 pub mod check_service_token;
+pub mod fingerprint;
+pub mod get_bearer_token;
+pub mod get_body_reader;
 pub mod get_service_token_data;
+pub mod get_token_app_id;
+pub mod ingestion_stats;
+pub mod pipeline;
+pub mod rate_limit;
+pub mod redact;
+pub mod sampling;
+pub mod severity;
+pub mod track_issue;
+pub mod webhook_mapping;
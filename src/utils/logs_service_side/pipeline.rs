@@ -0,0 +1,277 @@
+use std::{collections::HashMap, sync::Arc};
+
+use mongodb::{
+    bson::{doc, Bson, Document},
+    Collection, Database,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::AppState;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PipelineStep {
+    // Parse the message as a JSON object, optionally using one of its fields as the new message
+    Json {
+        message_field: Option<String>,
+    },
+    // Extract the named captures of the pattern into fields
+    Regex {
+        pattern: String,
+    },
+    // Use the value of a field as the log type
+    MapType {
+        field: String,
+    },
+    // Use the value of a field (unix seconds, unix milliseconds or RFC 3339) as the timestamp
+    Timestamp {
+        field: String,
+    },
+    // Drop the log when the field equals or matches the given value
+    Drop {
+        field: String,
+        equals: Option<String>,
+        matches: Option<String>,
+    },
+}
+
+// A step with its patterns compiled, built once when the pipeline is loaded
+#[derive(Debug)]
+pub enum CompiledStep {
+    Json {
+        message_field: Option<String>,
+    },
+    Regex {
+        regex: Regex,
+    },
+    MapType {
+        field: String,
+    },
+    Timestamp {
+        field: String,
+    },
+    Drop {
+        field: String,
+        equals: Option<String>,
+        matches: Option<Regex>,
+    },
+}
+
+// Compiled pipelines by service, along with the stored steps they were built from
+pub type PipelineCache = HashMap<String, (Bson, Arc<Vec<CompiledStep>>)>;
+
+#[derive(Debug, Serialize)]
+pub struct PipelineOutput {
+    pub r#type: Option<String>,
+    pub message: String,
+    pub timestamp: Option<i64>,
+    pub fields: Map<String, Value>,
+    pub dropped: bool,
+}
+
+pub fn compile_step(step: &PipelineStep) -> Result<CompiledStep, String> {
+    let compile =
+        |pattern: &str| Regex::new(pattern).map_err(|err| format!("Invalid pattern: {}", err));
+    let step = match step {
+        PipelineStep::Json { message_field } => CompiledStep::Json {
+            message_field: message_field.clone(),
+        },
+        PipelineStep::Regex { pattern } => CompiledStep::Regex {
+            regex: compile(pattern)?,
+        },
+        PipelineStep::MapType { field } => CompiledStep::MapType {
+            field: field.clone(),
+        },
+        PipelineStep::Timestamp { field } => CompiledStep::Timestamp {
+            field: field.clone(),
+        },
+        PipelineStep::Drop {
+            field,
+            equals,
+            matches,
+        } => {
+            if equals.is_none() && matches.is_none() {
+                return Err("A drop step needs `equals` or `matches`".to_string());
+            }
+            CompiledStep::Drop {
+                field: field.clone(),
+                equals: equals.clone(),
+                matches: matches.as_deref().map(compile).transpose()?,
+            }
+        }
+    };
+    Ok(step)
+}
+
+pub fn validate_pipeline(steps: &[PipelineStep]) -> Result<(), String> {
+    for step in steps {
+        compile_step(step)?;
+    }
+    Ok(())
+}
+
+// Steps saved before validation existed, or edited in the database, may be invalid
+pub fn compile_pipeline(steps: &[PipelineStep]) -> Vec<CompiledStep> {
+    let mut compiled = Vec::new();
+    for step in steps {
+        match compile_step(step) {
+            Ok(step) => compiled.push(step),
+            Err(err) => println!("❌ Skipping pipeline step: {}", err),
+        }
+    }
+    compiled
+}
+
+async fn get_stored_steps(db: &Database, app_id: &str) -> Option<Bson> {
+    let collection: Collection<Document> = db.collection("pipelines");
+    let pipeline = collection
+        .find_one(doc! { "app_id": app_id }, None)
+        .await
+        .unwrap()?;
+    pipeline.get("steps").cloned()
+}
+
+fn parse_steps(app_id: &str, steps: Bson) -> Option<Vec<PipelineStep>> {
+    match mongodb::bson::from_bson(steps) {
+        Ok(steps) => Some(steps),
+        Err(err) => {
+            println!("❌ Invalid pipeline for service {}: {}", app_id, err);
+            None
+        }
+    }
+}
+
+pub async fn get_pipeline(db: &Database, app_id: &str) -> Option<Vec<PipelineStep>> {
+    parse_steps(app_id, get_stored_steps(db, app_id).await?)
+}
+
+// Patterns are only compiled again when the stored steps change
+pub async fn get_compiled_pipeline(
+    app_state: &AppState,
+    app_id: &str,
+) -> Option<Arc<Vec<CompiledStep>>> {
+    let Some(steps) = get_stored_steps(&app_state.db, app_id).await else {
+        app_state.pipelines.lock().unwrap().remove(app_id);
+        return None;
+    };
+    if let Some((source, compiled)) = app_state.pipelines.lock().unwrap().get(app_id) {
+        if *source == steps {
+            return Some(compiled.clone());
+        }
+    }
+
+    let compiled = Arc::new(compile_pipeline(&parse_steps(app_id, steps.clone())?));
+    app_state
+        .pipelines
+        .lock()
+        .unwrap()
+        .insert(app_id.to_string(), (steps, compiled.clone()));
+    Some(compiled)
+}
+
+pub fn run_pipeline(
+    steps: &[CompiledStep],
+    r#type: Option<String>,
+    message: String,
+    timestamp: Option<i64>,
+    fields: Map<String, Value>,
+) -> PipelineOutput {
+    let mut output = PipelineOutput {
+        r#type,
+        message,
+        timestamp,
+        fields,
+        dropped: false,
+    };
+
+    for step in steps {
+        match step {
+            CompiledStep::Json { message_field } => {
+                // Steps are best effort, a message that isn't JSON is kept as is
+                if let Ok(Value::Object(object)) = serde_json::from_str(&output.message) {
+                    output.fields.extend(object);
+                    if let Some(message_field) = message_field {
+                        if let Some(message) = get_field(&output, message_field) {
+                            output.message = message;
+                        }
+                    }
+                }
+            }
+            CompiledStep::Regex { regex } => {
+                if let Some(captures) = regex.captures(&output.message) {
+                    for name in regex.capture_names().flatten() {
+                        if let Some(value) = captures.name(name) {
+                            output
+                                .fields
+                                .insert(name.to_string(), Value::from(value.as_str()));
+                        }
+                    }
+                }
+            }
+            CompiledStep::MapType { field } => {
+                if let Some(r#type) = get_field(&output, field) {
+                    output.r#type = Some(r#type);
+                }
+            }
+            CompiledStep::Timestamp { field } => {
+                if let Some(timestamp) = get_field(&output, field).and_then(|v| parse_timestamp(&v))
+                {
+                    output.timestamp = Some(timestamp);
+                }
+            }
+            CompiledStep::Drop {
+                field,
+                equals,
+                matches,
+            } => {
+                let value = get_field(&output, field);
+                if let Some(value) = value {
+                    let equal = equals.as_ref().is_some_and(|equals| *equals == value);
+                    let matching = matches
+                        .as_ref()
+                        .is_some_and(|matches| matches.is_match(&value));
+                    if equal || matching {
+                        output.dropped = true;
+                        return output;
+                    }
+                }
+            }
+        }
+    }
+
+    output
+}
+
+// `message` and `type` refer to the log itself, anything else is a dotted path into the fields
+fn get_field(output: &PipelineOutput, field: &str) -> Option<String> {
+    match field {
+        "message" => return Some(output.message.clone()),
+        "type" => return output.r#type.clone(),
+        _ => {}
+    }
+    let mut parts = field.split('.');
+    let mut value = output.fields.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<i64> {
+    if let Ok(number) = value.parse::<f64>() {
+        // Values below year 2286 in seconds are treated as seconds
+        if number < 10_000_000_000.0 {
+            return Some((number * 1000.0) as i64);
+        }
+        return Some(number as i64);
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date| date.timestamp_millis())
+}
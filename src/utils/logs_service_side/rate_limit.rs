@@ -0,0 +1,232 @@
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::{
+    bson::{doc, oid::ObjectId, Document},
+    options::UpdateOptions,
+};
+
+use crate::{
+    utils::send_notification::{send_discord_notification, send_telegram_notification},
+    AppState,
+};
+
+use super::ingestion_stats::{get_day, increment_ingestion_stat};
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: Instant,
+}
+
+// A value of 0 means unlimited
+#[derive(Debug, Clone)]
+pub struct ServiceLimits {
+    pub rate_limit_per_second: f64,
+    pub rate_limit_burst: f64,
+    pub daily_quota: i64,
+}
+
+// Limits set on the service override the ones from the config
+pub fn get_service_limits(app_state: &AppState, service: &Document) -> ServiceLimits {
+    let defaults = &app_state.conf.ingestion;
+    let rate_limit_per_second = service
+        .get_f64("rate_limit_per_second")
+        .unwrap_or(defaults.rate_limit_per_second);
+    let rate_limit_burst = service
+        .get_f64("rate_limit_burst")
+        .unwrap_or(defaults.rate_limit_burst);
+    ServiceLimits {
+        rate_limit_per_second,
+        // Without an explicit burst, allow one second worth of logs
+        rate_limit_burst: if rate_limit_burst > 0.0 {
+            rate_limit_burst
+        } else {
+            rate_limit_per_second
+        },
+        daily_quota: service
+            .get_i64("daily_quota")
+            .unwrap_or(defaults.daily_quota),
+    }
+}
+
+// Returns the current amount of tokens of a service after refilling its bucket
+pub fn refill_bucket(app_state: &AppState, app_id: &str, limits: &ServiceLimits) -> f64 {
+    let mut buckets = app_state.rate_limits.lock().unwrap();
+    let bucket = buckets.entry(app_id.to_string()).or_insert(TokenBucket {
+        tokens: limits.rate_limit_burst,
+        updated_at: Instant::now(),
+    });
+    let elapsed = bucket.updated_at.elapsed().as_secs_f64();
+    bucket.tokens =
+        (bucket.tokens + elapsed * limits.rate_limit_per_second).min(limits.rate_limit_burst);
+    bucket.updated_at = Instant::now();
+    bucket.tokens
+}
+
+// Takes `amount` tokens or returns the number of seconds to wait before retrying
+fn take_tokens(
+    app_state: &AppState,
+    app_id: &str,
+    limits: &ServiceLimits,
+    amount: f64,
+) -> Result<(), f64> {
+    refill_bucket(app_state, app_id, limits);
+    let mut buckets = app_state.rate_limits.lock().unwrap();
+    let bucket = buckets.get_mut(app_id).unwrap();
+    // Batches bigger than the burst are accepted on a full bucket and leave it in debt
+    let required = amount.min(limits.rate_limit_burst);
+    if bucket.tokens < required {
+        return Err((required - bucket.tokens) / limits.rate_limit_per_second);
+    }
+    bucket.tokens -= amount;
+    Ok(())
+}
+
+fn too_many_requests(retry_after: u64, message: String) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after.max(1).to_string())],
+        message,
+    )
+        .into_response()
+}
+
+pub async fn check_ingestion_limits(
+    app_state: Arc<AppState>,
+    app_id: &str,
+    amount: usize,
+) -> Result<(), Response> {
+    let db = &app_state.db;
+    let Ok(parsed_app_id) = ObjectId::parse_str(app_id) else {
+        return Ok(());
+    };
+    let service: Option<Document> = db
+        .collection("services")
+        .find_one(doc! { "_id": parsed_app_id }, None)
+        .await
+        .unwrap();
+    let Some(service) = service else {
+        // add_message reports deleted services
+        return Ok(());
+    };
+    let limits = get_service_limits(&app_state, &service);
+    let now = chrono::Utc::now();
+    let day = get_day(now.timestamp_millis());
+
+    // The quota is checked first so that rejected requests don't use rate limit tokens
+    if limits.daily_quota > 0 {
+        if !reserve_quota(db, app_id, &day, amount as i64, limits.daily_quota).await {
+            increment_ingestion_stat(db, app_id, "rejected", amount as i64).await;
+            notify_quota_exceeded(&app_state, app_id, &service, &day, limits.daily_quota).await;
+            let tomorrow = (now + chrono::Duration::days(1))
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc();
+            return Err(too_many_requests(
+                (tomorrow - now).num_seconds() as u64,
+                format!("Daily quota exceeded ({} logs per day)", limits.daily_quota),
+            ));
+        }
+    } else {
+        increment_ingestion_stat(db, app_id, "received", amount as i64).await;
+    }
+
+    if limits.rate_limit_per_second > 0.0 {
+        if let Err(retry_after) = take_tokens(&app_state, app_id, &limits, amount as f64) {
+            // Give back the reserved part of the quota
+            increment_ingestion_stat(db, app_id, "received", -(amount as i64)).await;
+            return Err(too_many_requests(
+                retry_after.ceil() as u64,
+                format!(
+                    "Rate limit exceeded ({} logs per second)",
+                    limits.rate_limit_per_second
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Counts `amount` received logs unless it would exceed the quota, in a single update so that
+// concurrent batches can't go over it
+async fn reserve_quota(
+    db: &mongodb::Database,
+    app_id: &str,
+    day: &str,
+    amount: i64,
+    daily_quota: i64,
+) -> bool {
+    if amount > daily_quota {
+        return false;
+    }
+    let collection = db.collection::<Document>("ingestion_stats");
+    // The stats of the day must exist for the conditional update to match them
+    collection
+        .update_one(
+            doc! { "app_id": app_id, "day": day },
+            doc! { "$setOnInsert": { "received": 0_i64 } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .unwrap();
+    let res = collection
+        .update_one(
+            doc! {
+                "app_id": app_id,
+                "day": day,
+                "$or": [
+                    { "received": { "$exists": false } },
+                    { "received": { "$lte": daily_quota - amount } },
+                ],
+            },
+            doc! { "$inc": { "received": amount } },
+            None,
+        )
+        .await
+        .unwrap();
+    res.matched_count == 1
+}
+
+// Only the first rejected request of the day sends a notification
+async fn notify_quota_exceeded(
+    app_state: &AppState,
+    app_id: &str,
+    service: &Document,
+    day: &str,
+    daily_quota: i64,
+) {
+    let res = app_state
+        .db
+        .collection::<Document>("ingestion_stats")
+        .update_one(
+            doc! { "app_id": app_id, "day": day, "quota_notified": { "$ne": true } },
+            doc! { "$set": { "quota_notified": true } },
+            None,
+        )
+        .await
+        .unwrap();
+    if res.modified_count == 0 {
+        return;
+    }
+
+    let app_name = service.get_str("app_name").unwrap_or(app_id);
+    send_discord_notification(format!(
+        "__{}__\n**quota exceeded**\nThe service reached its quota of {} logs for {}, new logs are rejected until tomorrow",
+        app_name, daily_quota, day
+    ))
+    .await;
+    send_telegram_notification(
+        &app_state.conf,
+        format!(
+            "<b>{}</b>\n<i>quota exceeded</i>\n\nThe service reached its quota of {} logs for {}, new logs are rejected until tomorrow",
+            app_name, daily_quota, day
+        ),
+    )
+    .await;
+}
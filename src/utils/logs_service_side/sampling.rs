@@ -0,0 +1,62 @@
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    Collection, Database,
+};
+use rand_core::{OsRng, RngCore};
+
+#[derive(Debug, PartialEq)]
+pub enum SamplingDecision {
+    Keep,
+    // Discarded by a sampling rate
+    Sampled,
+    // Discarded by a drop rule
+    Dropped,
+}
+
+// The most specific rule wins: service and type, then service, then type, then global
+fn rule_specificity(rule: &Document) -> u8 {
+    let has_app_id = rule.get_str("app_id").is_ok();
+    let has_type = rule.get_str("type_").is_ok();
+    match (has_app_id, has_type) {
+        (true, true) => 3,
+        (true, false) => 2,
+        (false, true) => 1,
+        (false, false) => 0,
+    }
+}
+
+pub async fn get_sampling_decision(db: &Database, app_id: &str, r#type: &str) -> SamplingDecision {
+    let collection: Collection<Document> = db.collection("sampling_rules");
+    let filter = doc! {
+        "app_id": { "$in": [null, app_id] },
+        "type_": { "$in": [null, r#type] },
+    };
+    let mut cursor = collection.find(filter, None).await.unwrap();
+
+    let mut rule: Option<Document> = None;
+    while let Some(candidate) = cursor.try_next().await.unwrap() {
+        if rule
+            .as_ref()
+            .is_none_or(|rule| rule_specificity(&candidate) > rule_specificity(rule))
+        {
+            rule = Some(candidate);
+        }
+    }
+
+    let Some(rule) = rule else {
+        return SamplingDecision::Keep;
+    };
+
+    if rule.get_bool("drop").unwrap_or(false) {
+        return SamplingDecision::Dropped;
+    }
+
+    let rate = rule.get_f64("rate").unwrap_or(1.0);
+    let draw = OsRng.next_u64() as f64 / u64::MAX as f64;
+    if draw < rate {
+        SamplingDecision::Keep
+    } else {
+        SamplingDecision::Sampled
+    }
+}
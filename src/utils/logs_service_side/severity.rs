@@ -0,0 +1,14 @@
+// Maps common severity names (OTLP, Loki, syslog keywords...) to Watchtower types
+pub fn get_severity_type(severity: &str) -> Option<String> {
+    let r#type = match severity.to_lowercase().as_str() {
+        "" => return None,
+        "trace" | "debug" | "dbug" => "debug",
+        "info" | "information" | "notice" => "info",
+        "warn" | "warning" => "warning",
+        "error" | "err" | "eror" => "error",
+        "fatal" | "critical" | "crit" | "alert" | "emergency" | "emerg" => "critical",
+        // Unknown severities are used as the type name
+        other => return Some(other.to_string()),
+    };
+    Some(r#type.to_string())
+}
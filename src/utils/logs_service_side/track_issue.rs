@@ -0,0 +1,61 @@
+use mongodb::{
+    bson::{doc, Document},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Collection, Database,
+};
+
+use super::fingerprint::normalize_message;
+
+#[derive(Debug, PartialEq)]
+pub enum IssueOccurrence {
+    // First time this fingerprint is seen
+    New,
+    // The issue was marked as resolved and happened again
+    Regression,
+    // The issue is already open
+    Known,
+}
+
+pub async fn track_issue(
+    db: &Database,
+    fingerprint: &str,
+    app_id: &str,
+    r#type: &str,
+    message: &str,
+    timestamp: i64,
+) -> IssueOccurrence {
+    let collection: Collection<Document> = db.collection("issues");
+    let previous = collection
+        .find_one_and_update(
+            doc! { "fingerprint": fingerprint },
+            doc! {
+                "$setOnInsert": {
+                    "app_id": app_id,
+                    "type_": r#type,
+                    "message": normalize_message(message),
+                },
+                // Logs can arrive out of order
+                "$min": { "first_seen": timestamp },
+                "$max": { "last_seen": timestamp },
+                "$set": {
+                    "last_message": message,
+                    "status": "open",
+                },
+                "$inc": { "count": 1_i64 },
+            },
+            FindOneAndUpdateOptions::builder()
+                .upsert(true)
+                .return_document(ReturnDocument::Before)
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    match previous {
+        None => IssueOccurrence::New,
+        Some(previous) if previous.get_str("status").unwrap_or("open") == "resolved" => {
+            IssueOccurrence::Regression
+        }
+        Some(_) => IssueOccurrence::Known,
+    }
+}
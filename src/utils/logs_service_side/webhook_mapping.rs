@@ -0,0 +1,100 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Extracts logs from an arbitrary JSON payload using JSON pointers (RFC 6901)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookMapping {
+    // Pointer to an array, each element becomes a log and the other pointers are relative to it
+    pub items: Option<String>,
+    // Template where `{/json/pointer}` is replaced by the value, e.g. "{/repository/full_name}: {/action}"
+    pub message: String,
+    // Pointer to the value used as the type, optionally translated with `type_map`
+    pub r#type: Option<String>,
+    pub type_map: Option<HashMap<String, String>>,
+    pub default_type: Option<String>,
+    // Field name to pointer
+    pub attributes: Option<HashMap<String, String>>,
+}
+
+pub struct MappedLog {
+    pub r#type: Option<String>,
+    pub message: String,
+    pub fields: Map<String, Value>,
+}
+
+fn get_placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{(/[^}]*)\}").unwrap())
+}
+
+pub fn validate_mapping(mapping: &WebhookMapping) -> Result<(), String> {
+    let pointers = mapping.items.iter().chain(mapping.r#type.iter()).chain(
+        mapping
+            .attributes
+            .iter()
+            .flat_map(|attributes| attributes.values()),
+    );
+    for pointer in pointers {
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Err(format!("Invalid JSON pointer: {}", pointer));
+        }
+    }
+    if mapping.message.is_empty() {
+        return Err("The message template can't be empty".to_string());
+    }
+    Ok(())
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+fn map_item(mapping: &WebhookMapping, item: &Value) -> MappedLog {
+    let message = get_placeholder_regex()
+        .replace_all(&mapping.message, |captures: &regex::Captures| {
+            item.pointer(&captures[1]).map(to_text).unwrap_or_default()
+        })
+        .to_string();
+
+    let value = mapping
+        .r#type
+        .as_ref()
+        .and_then(|pointer| item.pointer(pointer))
+        .map(to_text);
+    let r#type = match (value, &mapping.type_map) {
+        (Some(value), Some(type_map)) => type_map.get(&value).cloned(),
+        (value, _) => value,
+    }
+    .or(mapping.default_type.clone());
+
+    let mut fields = Map::new();
+    for (name, pointer) in mapping.attributes.iter().flatten() {
+        if let Some(value) = item.pointer(pointer) {
+            fields.insert(name.clone(), value.clone());
+        }
+    }
+
+    MappedLog {
+        r#type,
+        message,
+        fields,
+    }
+}
+
+pub fn apply_mapping(mapping: &WebhookMapping, payload: &Value) -> Result<Vec<MappedLog>, String> {
+    let Some(items) = &mapping.items else {
+        return Ok(vec![map_item(mapping, payload)]);
+    };
+    let items = payload
+        .pointer(items)
+        .and_then(|items| items.as_array())
+        .ok_or(format!("{} isn't an array in the payload", items))?;
+    Ok(items.iter().map(|item| map_item(mapping, item)).collect())
+}
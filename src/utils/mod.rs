@@ -1,6 +1,15 @@
+pub mod audit_log;
 pub mod check_auth_token;
+pub mod get_accessible_services;
+pub mod get_argon2;
 pub mod get_token_data;
+pub mod get_user_roles;
+pub mod get_viewable_apps;
 pub mod has_permission;
+pub mod has_role;
 pub mod hash_password;
+pub mod is_valid_role_grant;
 pub mod logs_service_side;
+pub mod send_notification;
 pub mod user;
+pub mod verify_password;
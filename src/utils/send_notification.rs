@@ -0,0 +1,51 @@
+use std::fs::File;
+
+use crate::config::Config;
+
+fn read_config_json() -> serde_json::Value {
+    let config_file = File::open("config.json").unwrap();
+    serde_json::from_reader(config_file).unwrap()
+}
+
+// Does nothing when no webhook has been set
+pub async fn send_discord_notification(content: String) {
+    let config_json = read_config_json();
+    let Some(discord_webhook) = config_json["discord_webhook"].as_str() else {
+        return;
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(discord_webhook)
+        .form(&serde_json::json!({ "content": content }))
+        .send()
+        .await;
+    if let Err(err) = res {
+        println!("❌ Failed to send discord notification: {}", err);
+    }
+}
+
+// Does nothing when no chat has been set, `text` is HTML formatted
+pub async fn send_telegram_notification(config: &Config, text: String) {
+    let config_json = read_config_json();
+    let Some(telegram_chat) = config_json["telegram_chat"].as_str() else {
+        return;
+    };
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            config.connections.telegram_token
+        ))
+        .form(&serde_json::json!({
+            "chat_id": telegram_chat,
+            "text": text,
+            "parse_mode": "HTML",
+        }))
+        .send()
+        .await;
+    if let Err(err) = res {
+        println!("❌ Failed to send telegram notification: {}", err);
+    }
+}
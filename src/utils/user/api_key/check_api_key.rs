@@ -0,0 +1,58 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::{structs::RoleGrant, utils::user::api_key::generate_api_key::hash_api_key};
+
+// last_used_at is only written once a minute to spare a write per request
+const LAST_USED_PRECISION_MS: i64 = 60 * 1000;
+
+pub struct ApiKeyOwner {
+    pub user_id: String,
+    pub api_key_id: String,
+    pub scopes: Vec<RoleGrant>,
+}
+
+// None when the key doesn't exist, was revoked or has expired
+pub async fn check_api_key(db: &mongodb::Database, api_key: &str) -> Option<ApiKeyOwner> {
+    let collection: mongodb::Collection<Document> = db.collection("api_keys");
+    let now = DateTime::now();
+    let api_key_doc = collection
+        .find_one(
+            doc! {
+                "key_hash": hash_api_key(api_key),
+                "$or": [{ "expires_at": null }, { "expires_at": { "$gt": now } }],
+            },
+            None,
+        )
+        .await
+        .unwrap()?;
+
+    let api_key_id = api_key_doc.get_str("_id").unwrap().to_string();
+    let last_used = DateTime::from_millis(now.timestamp_millis() - LAST_USED_PRECISION_MS);
+    collection
+        .update_one(
+            doc! {
+                "_id": &api_key_id,
+                "$or": [{ "last_used_at": null }, { "last_used_at": { "$lt": last_used } }],
+            },
+            doc! { "$set": { "last_used_at": now } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let scopes = api_key_doc
+        .get_array("scopes")
+        .map(|scopes| {
+            scopes
+                .iter()
+                .filter_map(|scope| mongodb::bson::from_bson(scope.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(ApiKeyOwner {
+        user_id: api_key_doc.get_str("user_id").unwrap().to_string(),
+        api_key_id,
+        scopes,
+    })
+}
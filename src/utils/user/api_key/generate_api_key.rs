@@ -0,0 +1,20 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// Tokens starting with it are API keys instead of user JWTs
+pub const API_KEY_PREFIX: &str = "wt_";
+
+// Only the hash of an API key is stored
+pub fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+// Returns the API key, its hash and the start of the key shown to identify it
+pub fn generate_api_key() -> (String, String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let api_key = format!("{}{}", API_KEY_PREFIX, hex::encode(bytes));
+    let hash = hash_api_key(&api_key);
+    let prefix = api_key[..API_KEY_PREFIX.len() + 8].to_string();
+    (api_key, hash, prefix)
+}
@@ -0,0 +1,2 @@
+pub mod check_api_key;
+pub mod generate_api_key;
@@ -0,0 +1,11 @@
+use mongodb::bson::{doc, oid::ObjectId, Document};
+
+// Databases linked to a service follow its roles, the others need a global role
+pub async fn get_db_app_id(db: &mongodb::Database, db_id: ObjectId) -> Option<String> {
+    let collection: mongodb::Collection<Document> = db.collection("databases");
+    collection
+        .find_one(doc! { "_id": db_id }, None)
+        .await
+        .unwrap()
+        .and_then(|database| database.get_str("app_id").ok().map(String::from))
+}
@@ -1,6 +1,7 @@
 pub mod check_db;
 pub mod connect_client;
 pub mod delete_save;
+pub mod get_db_app_id;
 pub mod save_db;
 pub mod secure_save_db;
 pub mod update_db_datas;
@@ -0,0 +1,22 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+// Seconds to wait before the next attempt for any of the keys, None when allowed
+pub async fn check_login_attempts(db: &mongodb::Database, keys: &[String]) -> Option<i64> {
+    let collection: mongodb::Collection<Document> = db.collection("login_attempts");
+    let now = DateTime::now();
+    let mut cursor = collection
+        .find(
+            doc! { "_id": { "$in": keys }, "blocked_until": { "$gt": now } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let mut retry_after: Option<i64> = None;
+    while cursor.advance().await.unwrap() {
+        let blocked_until = cursor.current().get_datetime("blocked_until").unwrap();
+        let wait = (blocked_until.timestamp_millis() - now.timestamp_millis() + 999) / 1000;
+        retry_after = Some(retry_after.map_or(wait, |retry_after| retry_after.max(wait)));
+    }
+    retry_after
+}
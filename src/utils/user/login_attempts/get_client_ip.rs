@@ -0,0 +1,30 @@
+use std::net::SocketAddr;
+
+use axum::http::HeaderMap;
+
+use crate::config::Config;
+
+// Proxies append to X-Forwarded-For, so only the entries added by the trusted ones can be
+// relied on: the client is the one `trusted_proxy_hops` from the right, anything before it
+// is whatever the client sent
+pub fn get_client_ip(config: &Config, headers: &HeaderMap, addr: SocketAddr) -> String {
+    let protection = &config.security.login_protection;
+    if protection.trust_forwarded_for {
+        let forwarded_for = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        let forwarded_for = forwarded_for
+            .len()
+            .checked_sub(protection.trusted_proxy_hops.max(1))
+            .and_then(|index| forwarded_for.get(index))
+            .map(|ip| ip.trim())
+            .filter(|ip| !ip.is_empty());
+        if let Some(ip) = forwarded_for {
+            return ip.to_string();
+        }
+    }
+    addr.ip().to_string()
+}
@@ -0,0 +1,5 @@
+pub mod check_login_attempts;
+pub mod get_client_ip;
+pub mod record_login_failure;
+pub mod release_login_attempt;
+pub mod reserve_login_attempt;
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::{
+    utils::send_notification::{send_discord_notification, send_telegram_notification},
+    AppState,
+};
+
+// `key` is "username:<username>" or "ip:<ip>", `failures` counts the attempt reserved by
+// reserve_login_attempt. The admin channel is notified on lockout.
+pub async fn record_login_failure(app_state: &Arc<AppState>, key: &str, failures: u32) {
+    let protection = &app_state.conf.security.login_protection;
+    let collection: mongodb::Collection<Document> = app_state.db.collection("login_attempts");
+    let now = chrono::Utc::now();
+    let window_end = now + chrono::Duration::minutes(protection.window_minutes);
+
+    let locked = failures >= protection.max_attempts;
+    let blocked_until = if locked {
+        now + chrono::Duration::minutes(protection.lockout_minutes)
+    } else {
+        let delay = protection
+            .backoff_base_secs
+            .saturating_mul(1 << (failures - 1).min(30))
+            .min(protection.backoff_max_secs);
+        now + chrono::Duration::seconds(delay)
+    };
+    // The counter must outlive the lockout
+    let expires_at = blocked_until.max(window_end);
+    // Parallel failures may record their delays in any order, the longest one is kept
+    collection
+        .update_one(
+            doc! { "_id": key },
+            doc! {
+                "$max": {
+                    "blocked_until": DateTime::from_millis(blocked_until.timestamp_millis()),
+                    "expires_at": DateTime::from_millis(expires_at.timestamp_millis()),
+                    "locked": locked,
+                },
+                "$set": {
+                    "last_failure_at": DateTime::from_millis(now.timestamp_millis()),
+                },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Only notify once, when the threshold is reached
+    if failures != protection.max_attempts {
+        return;
+    }
+    println!("🔒 Login locked for {} after {} failures", key, failures);
+    send_discord_notification(format!(
+        "**login locked**\n`{}` is locked for {} minutes after {} failed login attempts",
+        key, protection.lockout_minutes, failures
+    ))
+    .await;
+    send_telegram_notification(
+        &app_state.conf,
+        format!(
+            "<i>login locked</i>\n\n<code>{}</code> is locked for {} minutes after {} failed login attempts",
+            key, protection.lockout_minutes, failures
+        ),
+    )
+    .await;
+}
@@ -0,0 +1,14 @@
+use mongodb::bson::{doc, Document};
+
+// Gives back an attempt reserved by a successful login
+pub async fn release_login_attempt(db: &mongodb::Database, key: &str) {
+    let collection: mongodb::Collection<Document> = db.collection("login_attempts");
+    collection
+        .update_one(
+            doc! { "_id": key, "failures": { "$gt": 0 } },
+            doc! { "$inc": { "failures": -1 } },
+            None,
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use mongodb::{
+    bson::{doc, DateTime, Document},
+    options::{FindOneAndUpdateOptions, ReturnDocument, UpdateOptions},
+};
+
+use crate::{utils::user::login_attempts::check_login_attempts::check_login_attempts, AppState};
+
+// Counts the attempt before the password is checked, so that parallel guesses can't all pass
+// before the first failure is recorded. Returns the attempts of the key including this one,
+// or the seconds to wait when it is blocked.
+pub async fn reserve_login_attempt(app_state: &Arc<AppState>, key: &str) -> Result<u32, i64> {
+    let protection = &app_state.conf.security.login_protection;
+    let collection: mongodb::Collection<Document> = app_state.db.collection("login_attempts");
+    let now = chrono::Utc::now();
+
+    // MongoDB only removes expired counters every minute
+    collection
+        .delete_one(
+            doc! { "_id": key, "expires_at": { "$lte": DateTime::now() } },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let window_end = DateTime::from_millis(
+        (now + chrono::Duration::minutes(protection.window_minutes)).timestamp_millis(),
+    );
+    collection
+        .update_one(
+            doc! { "_id": key },
+            doc! { "$setOnInsert": { "failures": 0, "expires_at": window_end } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .unwrap();
+
+    // The block check and the increment are a single update
+    let attempts = collection
+        .find_one_and_update(
+            doc! {
+                "_id": key,
+                "blocked_until": { "$not": { "$gt": DateTime::from_millis(now.timestamp_millis()) } },
+            },
+            doc! {
+                "$inc": { "failures": 1 },
+                "$max": { "expires_at": window_end },
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .unwrap();
+    let Some(attempts) = attempts else {
+        let retry_after = check_login_attempts(&app_state.db, &[key.to_string()]).await;
+        return Err(retry_after.unwrap_or(1));
+    };
+    let failures = attempts.get_i32("failures").unwrap_or(1).max(1) as u32;
+
+    // Parallel attempts went over the limit before the lockout was recorded
+    if failures > protection.max_attempts {
+        let blocked_until = DateTime::from_millis(
+            (now + chrono::Duration::minutes(protection.lockout_minutes)).timestamp_millis(),
+        );
+        collection
+            .update_one(
+                doc! { "_id": key },
+                doc! {
+                    "$max": { "blocked_until": blocked_until, "expires_at": blocked_until },
+                    "$set": { "locked": true },
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        return Err(protection.lockout_minutes * 60);
+    }
+
+    Ok(failures)
+}
@@ -1 +1,6 @@
+pub mod api_key;
 pub mod db;
+pub mod login_attempts;
+pub mod oidc;
+pub mod session;
+pub mod totp;
@@ -0,0 +1,47 @@
+use crate::{config::Oidc, utils::user::oidc::get_oidc_discovery::OidcDiscovery};
+
+// Returns the ID token obtained for the authorization code
+pub async fn exchange_oidc_code(
+    oidc: &Oidc,
+    discovery: &OidcDiscovery,
+    code: &str,
+    code_verifier: &str,
+) -> Result<String, String> {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", oidc.redirect_uri.as_str()),
+        ("client_id", oidc.client_id.as_str()),
+        ("code_verifier", code_verifier),
+    ];
+    if let Some(client_secret) = &oidc.client_secret {
+        form.push(("client_secret", client_secret.as_str()));
+    }
+
+    let client = reqwest::Client::new();
+    let res = client
+        .post(&discovery.token_endpoint)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|err| format!("Token request failed: {}", err))?;
+    let status = res.status();
+    let bytes = res
+        .bytes()
+        .await
+        .map_err(|err| format!("Failed to read the token response: {}", err))?;
+    if !status.is_success() {
+        return Err(format!(
+            "Token endpoint answered {}: {}",
+            status,
+            String::from_utf8_lossy(&bytes)
+        ));
+    }
+
+    let body: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|err| format!("Invalid token response: {}", err))?;
+    body["id_token"]
+        .as_str()
+        .map(String::from)
+        .ok_or("The token response has no id_token".to_string())
+}
@@ -0,0 +1,89 @@
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::config::Oidc;
+
+#[derive(Debug, Deserialize)]
+pub struct OidcDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+// reqwest is built without its json feature
+pub async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, String> {
+    let res = reqwest::get(url)
+        .await
+        .map_err(|err| format!("Request to {} failed: {}", url, err))?;
+    if !res.status().is_success() {
+        return Err(format!("{} answered {}", url, res.status()));
+    }
+    let bytes = res
+        .bytes()
+        .await
+        .map_err(|err| format!("Failed to read {}: {}", url, err))?;
+    serde_json::from_slice(&bytes).map_err(|err| format!("Invalid JSON from {}: {}", url, err))
+}
+
+// Fetched on each login so that changes of the provider are picked up.
+// The document must be about the configured issuer (OpenID Connect Discovery 4.3).
+pub async fn get_oidc_discovery(oidc: &Oidc) -> Result<OidcDiscovery, String> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        oidc.issuer_url.trim_end_matches('/')
+    );
+    let discovery: OidcDiscovery = get_json(&url).await?;
+    if discovery.issuer != oidc.issuer_url {
+        return Err(format!(
+            "The provider announces the issuer {} instead of {}",
+            discovery.issuer, oidc.issuer_url
+        ));
+    }
+    Ok(discovery)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use watchtower_test_support::StubServer;
+
+    fn oidc(issuer_url: &str) -> Oidc {
+        toml::from_str(&format!(
+            r#"
+            issuer_url = "{}"
+            client_id = "watchtower"
+            redirect_uri = "https://watchtower.example.com/oidc/callback"
+            "#,
+            issuer_url
+        ))
+        .unwrap()
+    }
+
+    fn discovery(issuer: &str) -> String {
+        json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn accepts_configured_issuer() {
+        let server = StubServer::start().await;
+        server.respond(200, &discovery(&server.url));
+        let discovery = get_oidc_discovery(&oidc(&server.url)).await.unwrap();
+        assert_eq!(discovery.issuer, server.url);
+        assert_eq!(server.requests()[0].0, "/.well-known/openid-configuration");
+    }
+
+    #[tokio::test]
+    async fn rejects_another_issuer() {
+        let server = StubServer::start().await;
+        server.respond(200, &discovery("https://evil.example.com"));
+        assert!(get_oidc_discovery(&oidc(&server.url)).await.is_err());
+    }
+}
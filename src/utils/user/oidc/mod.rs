@@ -0,0 +1,5 @@
+pub mod exchange_oidc_code;
+pub mod get_oidc_discovery;
+pub mod provision_oidc_user;
+pub mod state_cookie;
+pub mod validate_id_token;
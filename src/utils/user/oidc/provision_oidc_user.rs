@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use mongodb::bson::{doc, Document};
+
+use crate::{config::Oidc, structs::RoleGrant, AppState};
+
+// Groups of the provider, the claim can be a list or a single group
+fn get_groups(oidc: &Oidc, claims: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+    match claims.get(&oidc.groups_claim) {
+        Some(serde_json::Value::Array(groups)) => groups
+            .iter()
+            .filter_map(|group| group.as_str().map(String::from))
+            .collect(),
+        Some(serde_json::Value::String(group)) => vec![group.clone()],
+        _ => Vec::new(),
+    }
+}
+
+fn get_role_grants(oidc: &Oidc, groups: &[String]) -> Vec<RoleGrant> {
+    let mut grants: Vec<RoleGrant> = Vec::new();
+    for mapping in &oidc.group_roles {
+        if !groups.contains(&mapping.group) {
+            continue;
+        }
+        let grant = RoleGrant {
+            role: mapping.role,
+            scope: mapping.scope,
+            target: mapping.target.clone(),
+        };
+        if !grants.contains(&grant) {
+            grants.push(grant);
+        }
+    }
+    grants
+}
+
+fn get_username(oidc: &Oidc, claims: &serde_json::Map<String, serde_json::Value>) -> String {
+    claims
+        .get(&oidc.username_claim)
+        .or_else(|| claims.get("email"))
+        .or_else(|| claims.get("sub"))
+        .and_then(|username| username.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+// `existing` is the user already holding the username, which never belongs to this provider
+// account since those are matched by subject first
+fn check_new_username(username: &str, existing: Option<&Document>) -> Result<(), String> {
+    if username.is_empty() || username == "root" || existing.is_some() {
+        return Err(format!(
+            "The username {} is already used by another account",
+            username
+        ));
+    }
+    Ok(())
+}
+
+// Finds or creates the user of the provider account, returns its id.
+// Accounts are matched by the configured issuer and subject, never by username, so that an existing
+// local user can't be taken over through the provider.
+pub async fn provision_oidc_user(
+    app_state: &Arc<AppState>,
+    oidc: &Oidc,
+    claims: &serde_json::Map<String, serde_json::Value>,
+) -> Result<String, String> {
+    let sub = claims.get("sub").and_then(|sub| sub.as_str()).unwrap();
+    let oidc_subject = format!("{}|{}", oidc.issuer_url, sub);
+    let grants = get_role_grants(oidc, &get_groups(oidc, claims));
+    let roles = mongodb::bson::to_bson(&grants).unwrap();
+
+    let db = &app_state.db;
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user = collection
+        .find_one(doc! { "oidc_subject": &oidc_subject }, None)
+        .await
+        .unwrap();
+
+    if let Some(user) = user {
+        let object_id = user.get_object_id("_id").unwrap();
+        if !oidc.group_roles.is_empty() {
+            collection
+                .update_one(
+                    doc! { "_id": object_id },
+                    doc! { "$set": { "roles": roles } },
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        return Ok(object_id.to_hex());
+    }
+
+    let username = get_username(oidc, claims);
+    let existing = collection
+        .find_one(doc! { "username": &username }, None)
+        .await
+        .unwrap();
+    check_new_username(&username, existing.as_ref())?;
+
+    // No password, these users can only log in through the provider
+    let user = doc! {
+        "username": &username,
+        "password": "",
+        "permissions": [],
+        "roles": roles,
+        "oidc_subject": oidc_subject,
+    };
+    let res = collection.insert_one(user, None).await.unwrap();
+    println!("👤 Created user {} from single sign-on", username);
+    Ok(res.inserted_id.as_object_id().unwrap().to_hex())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::structs::{Role, RoleScope};
+
+    fn oidc() -> Oidc {
+        toml::from_str(
+            r#"
+            issuer_url = "https://idp.example.com"
+            client_id = "watchtower"
+            redirect_uri = "https://watchtower.example.com/oidc/callback"
+
+            [[group_roles]]
+            group = "ops"
+            role = "operator"
+            scope = "global"
+
+            [[group_roles]]
+            group = "payments"
+            role = "viewer"
+            scope = "group"
+            target = "payments"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn claims(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn refuses_root() {
+        assert!(check_new_username("root", None).is_err());
+    }
+
+    #[test]
+    fn refuses_username_of_a_local_account() {
+        let local_user = doc! { "username": "alice", "password": "$argon2id$..." };
+        assert!(check_new_username("alice", Some(&local_user)).is_err());
+    }
+
+    #[test]
+    fn refuses_username_of_another_provider_account() {
+        let other = doc! { "username": "alice", "oidc_subject": "https://idp.example.com|other" };
+        assert!(check_new_username("alice", Some(&other)).is_err());
+    }
+
+    #[test]
+    fn accepts_free_username() {
+        assert!(check_new_username("alice", None).is_ok());
+        assert!(check_new_username("", None).is_err());
+    }
+
+    #[test]
+    fn username_falls_back_to_email_then_subject() {
+        let oidc = oidc();
+        let username = |value| get_username(&oidc, &claims(value));
+        assert_eq!(
+            username(
+                json!({ "sub": "1", "preferred_username": "alice", "email": "a@example.com" })
+            ),
+            "alice"
+        );
+        assert_eq!(
+            username(json!({ "sub": "1", "email": "a@example.com" })),
+            "a@example.com"
+        );
+        assert_eq!(username(json!({ "sub": "1" })), "1");
+    }
+
+    #[test]
+    fn maps_groups_to_roles() {
+        let oidc = oidc();
+        let groups = get_groups(
+            &oidc,
+            &claims(json!({ "groups": ["payments", "ops", "ops"] })),
+        );
+        assert_eq!(
+            get_role_grants(&oidc, &groups),
+            vec![
+                RoleGrant {
+                    role: Role::Operator,
+                    scope: RoleScope::Global,
+                    target: None,
+                },
+                RoleGrant {
+                    role: Role::Viewer,
+                    scope: RoleScope::Group,
+                    target: Some("payments".to_string()),
+                },
+            ]
+        );
+
+        let groups = get_groups(&oidc, &claims(json!({ "groups": "ops" })));
+        assert_eq!(get_role_grants(&oidc, &groups).len(), 1);
+        let groups = get_groups(&oidc, &claims(json!({})));
+        assert!(get_role_grants(&oidc, &groups).is_empty());
+    }
+}
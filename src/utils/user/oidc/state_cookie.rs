@@ -0,0 +1,61 @@
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+
+// HttpOnly cookie binding a login to the browser that started it, holds the hash of the state
+pub const STATE_COOKIE: &str = "watchtower_oidc_state";
+const STATE_COOKIE_PATH: &str = "/oidc";
+
+fn hash_state(state: &str) -> String {
+    hex::encode(Sha256::digest(state.as_bytes()))
+}
+
+// Lax so that the cookie comes back with the redirect of the provider
+pub fn set_state_cookie(
+    config: &Config,
+    jar: CookieJar,
+    state: &str,
+    max_age_minutes: i64,
+) -> CookieJar {
+    let cookie = Cookie::build(STATE_COOKIE, hash_state(state))
+        .path(STATE_COOKIE_PATH)
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(config.jwt.secure_cookie)
+        .max_age(time::Duration::minutes(max_age_minutes))
+        .finish();
+    jar.add(cookie)
+}
+
+pub fn check_state_cookie(jar: &CookieJar, state: &str) -> bool {
+    jar.get(STATE_COOKIE)
+        .is_some_and(|cookie| cookie.value() == hash_state(state))
+}
+
+pub fn clear_state_cookie(jar: CookieJar) -> CookieJar {
+    jar.remove(
+        Cookie::build(STATE_COOKIE, "")
+            .path(STATE_COOKIE_PATH)
+            .finish(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_state_of_the_cookie_only() {
+        let jar = CookieJar::new().add(Cookie::new(STATE_COOKIE, hash_state("state")));
+        assert!(check_state_cookie(&jar, "state"));
+        assert!(!check_state_cookie(&jar, "other"));
+        assert!(!check_state_cookie(&CookieJar::new(), "state"));
+    }
+
+    #[test]
+    fn does_not_store_the_state() {
+        let jar = CookieJar::new().add(Cookie::new(STATE_COOKIE, "state"));
+        assert!(!check_state_cookie(&jar, "state"));
+    }
+}
@@ -0,0 +1,14 @@
+use mongodb::bson::{doc, Document};
+
+// Tokens issued before sessions existed have no jti and are rejected
+pub async fn check_session(db: &mongodb::Database, user_id: &str, session_id: &str) -> bool {
+    if session_id.is_empty() {
+        return false;
+    }
+    let collection: mongodb::Collection<Document> = db.collection("sessions");
+    collection
+        .find_one(doc! { "_id": session_id, "user_id": user_id }, None)
+        .await
+        .unwrap()
+        .is_some()
+}
@@ -0,0 +1,32 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::utils::user::session::new_refresh_token::new_refresh_token;
+
+// Returns the id of the session, stored as the `jti` claim of access tokens,
+// and the first refresh token of the session
+pub async fn create_session(
+    db: &mongodb::Database,
+    user_id: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    user_agent: Option<String>,
+) -> (String, String) {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (refresh_token, refresh_token_hash) = new_refresh_token();
+    let collection: mongodb::Collection<Document> = db.collection("sessions");
+    collection
+        .insert_one(
+            doc! {
+                "_id": session_id.clone(),
+                "user_id": user_id,
+                "created_at": DateTime::now(),
+                "expires_at": DateTime::from_millis(expires_at.timestamp_millis()),
+                "user_agent": user_agent,
+                "refresh_token_hash": refresh_token_hash,
+                "used_refresh_token_hashes": [],
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    (session_id, refresh_token)
+}
@@ -0,0 +1,36 @@
+use mongodb::bson::{doc, Document};
+
+use crate::structs;
+
+// Most recent first, `current_session_id` is flagged as the current session
+pub async fn get_sessions(
+    db: &mongodb::Database,
+    user_id: &str,
+    current_session_id: Option<&str>,
+) -> Result<Vec<structs::Session>, mongodb::error::Error> {
+    let collection: mongodb::Collection<Document> = db.collection("sessions");
+    let mut cursor = collection
+        .find(
+            doc! { "user_id": user_id },
+            mongodb::options::FindOptions::builder()
+                .sort(doc! { "created_at": -1 })
+                .build(),
+        )
+        .await?;
+
+    let mut result: Vec<structs::Session> = Vec::new();
+    while cursor.advance().await? {
+        let doc = cursor.current();
+        let _id = doc.get_str("_id").unwrap();
+        let session = structs::Session {
+            _id: _id.to_string(),
+            created_at: doc.get_datetime("created_at").unwrap().timestamp_millis(),
+            expires_at: doc.get_datetime("expires_at").unwrap().timestamp_millis(),
+            user_agent: doc.get_str("user_agent").ok().map(|ua| ua.to_string()),
+            current: current_session_id == Some(_id),
+        };
+        result.push(session);
+    }
+
+    Ok(result)
+}
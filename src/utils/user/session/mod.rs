@@ -0,0 +1,6 @@
+pub mod check_session;
+pub mod create_session;
+pub mod get_sessions;
+pub mod new_refresh_token;
+pub mod revoke_sessions;
+pub mod rotate_refresh_token;
@@ -0,0 +1,16 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// Only the hash of a refresh token is stored
+pub fn hash_refresh_token(refresh_token: &str) -> String {
+    hex::encode(Sha256::digest(refresh_token.as_bytes()))
+}
+
+// Returns the refresh token and its hash
+pub fn new_refresh_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let refresh_token = hex::encode(bytes);
+    let hash = hash_refresh_token(&refresh_token);
+    (refresh_token, hash)
+}
@@ -0,0 +1,15 @@
+use mongodb::bson::{doc, Document};
+
+// Revokes every session of the user but `except`, returns how many were revoked
+pub async fn revoke_sessions(db: &mongodb::Database, user_id: &str, except: Option<&str>) -> u64 {
+    let mut filter = doc! { "user_id": user_id };
+    if let Some(except) = except {
+        filter.insert("_id", doc! { "$ne": except });
+    }
+    let collection: mongodb::Collection<Document> = db.collection("sessions");
+    collection
+        .delete_many(filter, None)
+        .await
+        .unwrap()
+        .deleted_count
+}
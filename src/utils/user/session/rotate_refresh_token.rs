@@ -0,0 +1,58 @@
+use mongodb::bson::{doc, DateTime, Document};
+
+use crate::utils::user::session::new_refresh_token::{hash_refresh_token, new_refresh_token};
+
+pub enum RefreshCheck {
+    Rotated {
+        user_id: String,
+        session_id: String,
+        refresh_token: String,
+        expires_at: i64,
+    },
+    // An already rotated token was presented again, the session has been revoked
+    Reused,
+    Invalid,
+}
+
+// Replaces the refresh token of its session by a new one
+pub async fn rotate_refresh_token(db: &mongodb::Database, refresh_token: &str) -> RefreshCheck {
+    let hash = hash_refresh_token(refresh_token);
+    let (new_refresh_token, new_hash) = new_refresh_token();
+    let collection: mongodb::Collection<Document> = db.collection("sessions");
+
+    // Atomic so that two concurrent refreshes can't both succeed
+    let session = collection
+        .find_one_and_update(
+            doc! { "refresh_token_hash": &hash, "expires_at": { "$gt": DateTime::now() } },
+            doc! {
+                "$set": { "refresh_token_hash": new_hash },
+                "$push": { "used_refresh_token_hashes": &hash },
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    if let Some(session) = session {
+        return RefreshCheck::Rotated {
+            user_id: session.get_str("user_id").unwrap().to_string(),
+            session_id: session.get_str("_id").unwrap().to_string(),
+            refresh_token: new_refresh_token,
+            expires_at: session
+                .get_datetime("expires_at")
+                .unwrap()
+                .timestamp_millis(),
+        };
+    }
+
+    // The token may have been stolen, revoke the whole family
+    let reused = collection
+        .delete_one(doc! { "used_refresh_token_hashes": &hash }, None)
+        .await
+        .unwrap()
+        .deleted_count;
+    if reused > 0 {
+        return RefreshCheck::Reused;
+    }
+
+    RefreshCheck::Invalid
+}
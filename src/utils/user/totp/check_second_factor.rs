@@ -0,0 +1,48 @@
+use mongodb::bson::{doc, Document};
+
+use crate::utils::user::totp::{
+    generate_recovery_codes::hash_recovery_code, verify_totp::verify_totp,
+};
+
+// Accepts a TOTP code or a recovery code, which can only be used once
+pub async fn check_second_factor(db: &mongodb::Database, user: &Document, code: &str) -> bool {
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user_id = user.get_object_id("_id").unwrap();
+
+    if let Ok(secret) = user.get_str("totp_secret") {
+        let last_step = user.get_i64("totp_last_step").ok();
+        if let Some(step) = verify_totp(secret, code, last_step) {
+            // Only one of concurrent requests with the same code can move the step forward
+            let res = collection
+                .update_one(
+                    doc! {
+                        "_id": user_id,
+                        "totp_secret": secret,
+                        "$or": [
+                            { "totp_last_step": { "$exists": false } },
+                            { "totp_last_step": { "$lt": step } },
+                        ],
+                    },
+                    doc! { "$set": { "totp_last_step": step } },
+                    None,
+                )
+                .await
+                .unwrap();
+            if res.modified_count > 0 {
+                return true;
+            }
+        }
+    }
+
+    let hash = hash_recovery_code(code);
+    collection
+        .update_one(
+            doc! { "_id": user_id, "recovery_codes": &hash },
+            doc! { "$pull": { "recovery_codes": &hash } },
+            None,
+        )
+        .await
+        .unwrap()
+        .modified_count
+        > 0
+}
@@ -0,0 +1,25 @@
+use data_encoding::BASE32_NOPAD;
+use rand_core::{OsRng, RngCore};
+
+// 160 bits secret as recommended by RFC 4226, base32 encoded for authenticator apps
+pub fn generate_totp_secret() -> String {
+    let mut bytes = [0u8; 20];
+    OsRng.fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+// URI of the QR code scanned by authenticator apps
+pub fn get_totp_uri(secret: &str, username: &str) -> String {
+    let mut uri = reqwest::Url::parse("otpauth://totp/").unwrap();
+    uri.path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .push(&format!("WatchTower:{}", username));
+    uri.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", "WatchTower")
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", "6")
+        .append_pair("period", "30");
+    uri.to_string()
+}
@@ -0,0 +1,15 @@
+use mongodb::bson::{doc, Document};
+
+// TOTP enabled, or a single sign-on account: those can only log in through the provider,
+// which is in charge of their second factor
+pub async fn has_second_factor(db: &mongodb::Database, user_id: &str) -> bool {
+    let object_id = mongodb::bson::oid::ObjectId::parse_str(user_id).unwrap();
+    let collection: mongodb::Collection<Document> = db.collection("users");
+    let user = collection
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .unwrap();
+    user.is_some_and(|user| {
+        user.get_bool("totp_enabled").unwrap_or(false) || user.get_str("oidc_subject").is_ok()
+    })
+}
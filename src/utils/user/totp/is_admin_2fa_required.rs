@@ -0,0 +1,10 @@
+use std::fs::File;
+
+// Set by administrators with /set_require_admin_2fa, stored in config.json
+pub fn is_admin_2fa_required() -> bool {
+    let Ok(config_file) = File::open("config.json") else {
+        return false;
+    };
+    let config_json: serde_json::Value = serde_json::from_reader(config_file).unwrap_or_default();
+    config_json["require_admin_2fa"].as_bool().unwrap_or(false)
+}
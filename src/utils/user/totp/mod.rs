@@ -0,0 +1,6 @@
+pub mod check_second_factor;
+pub mod generate_recovery_codes;
+pub mod generate_totp_secret;
+pub mod has_second_factor;
+pub mod is_admin_2fa_required;
+pub mod verify_totp;
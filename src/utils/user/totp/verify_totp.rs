@@ -0,0 +1,36 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+const STEP_SECONDS: i64 = 30;
+
+// RFC 6238 with SHA1 and 6 digits
+fn totp_code(key: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).unwrap();
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    binary % 1_000_000
+}
+
+// Returns the time step of the code, the previous and next steps are accepted for clock drift.
+// Codes of `last_step` or earlier were already used and are rejected.
+pub fn verify_totp(secret: &str, code: &str, last_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != 6 {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+
+    let current_step = chrono::Utc::now().timestamp() / STEP_SECONDS;
+    (current_step - 1..=current_step + 1)
+        .filter(|step| last_step.is_none_or(|last_step| *step > last_step))
+        .find(|step| totp_code(&key, *step) == code)
+}
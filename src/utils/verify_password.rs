@@ -0,0 +1,45 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordVerifier, SaltString},
+    Algorithm, Params,
+};
+
+use crate::{config::Config, utils::get_argon2::get_argon2};
+
+#[derive(Debug, PartialEq)]
+pub enum PasswordCheck {
+    Invalid,
+    Valid,
+    // Valid but hashed with the global salt or outdated parameters
+    NeedsRehash,
+}
+
+pub fn verify_password(config: &Config, password: &str, password_hash: &str) -> PasswordCheck {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return PasswordCheck::Invalid;
+    };
+    // The hash holds its own salt and parameters, so legacy hashes still verify
+    let argon2 = get_argon2(config);
+    if argon2
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        return PasswordCheck::Invalid;
+    }
+
+    let legacy_salt = SaltString::encode_b64(config.security.password_salt.as_bytes()).ok();
+    let is_legacy = legacy_salt.is_some_and(|legacy_salt| {
+        parsed_hash.salt.map(|salt| salt.as_str()) == Some(legacy_salt.as_str())
+    });
+    let params = &config.security.argon2;
+    let outdated = parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || Params::try_from(&parsed_hash).map_or(true, |hash_params| {
+            hash_params.m_cost() != params.memory_cost
+                || hash_params.t_cost() != params.time_cost
+                || hash_params.p_cost() != params.parallelism
+        });
+    if is_legacy || outdated {
+        PasswordCheck::NeedsRehash
+    } else {
+        PasswordCheck::Valid
+    }
+}
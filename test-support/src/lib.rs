@@ -0,0 +1,131 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+// Path and body of a request
+pub type Request = (String, Vec<u8>);
+
+// Status, extra headers and body of a response
+type Response = (u16, Vec<(String, String)>, String);
+
+// Minimal HTTP/1.1 server recording the requests, answers with the queued responses then 200
+#[derive(Clone)]
+pub struct StubServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+    responses: Arc<Mutex<VecDeque<Response>>>,
+}
+
+impl StubServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = StubServer {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            requests: Arc::default(),
+            responses: Arc::default(),
+        };
+        let handle = server.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle.clone().serve(stream));
+            }
+        });
+        server
+    }
+
+    pub fn respond(&self, status: u16, body: &str) {
+        self.respond_with_headers(status, &[], body);
+    }
+
+    pub fn respond_with_headers(&self, status: u16, headers: &[(&str, &str)], body: &str) {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back((status, headers, body.to_string()));
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    async fn serve(self, stream: TcpStream) {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            let path = request_line
+                .split(' ')
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                if reader.read_line(&mut header).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            if reader.read_exact(&mut body).await.is_err() {
+                return;
+            }
+            self.requests.lock().unwrap().push((path, body));
+
+            let (status, headers, body) = self.responses.lock().unwrap().pop_front().unwrap_or((
+                200,
+                Vec::new(),
+                "{}".to_string(),
+            ));
+            let headers: String = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                .collect();
+            let response = format!(
+                "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}\r\n{}",
+                status,
+                body.len(),
+                headers,
+                body
+            );
+            if reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+// Empty directory unique to the test
+pub fn temp_dir(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("watchtower-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+}
@@ -0,0 +1,48 @@
+// Request and response types of the service side API, shared by the server and the clients
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LogInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    pub message: String,
+    // Unix timestamp in milliseconds, defaults to the reception time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    // Structured fields, must be a JSON object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<serde_json::Value>,
+    // Logs sharing a trace_id can be followed across services with /get_trace
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+}
+
+// Body of /service/add_messages
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddMessagesInput {
+    pub token: String,
+    pub app_id: Option<String>,
+    pub logs: Vec<LogInput>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LineError {
+    pub line: usize,
+    pub error: String,
+}
+
+// Response of /service/add_messages for NDJSON bodies
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddMessagesSummary {
+    pub status: String,
+    pub message: Option<String>,
+    pub added: usize,
+    pub failed: usize,
+    pub errors: Vec<LineError>,
+}